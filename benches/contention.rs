@@ -0,0 +1,44 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+//! Measures interning throughput as the number of threads grows. With a
+//! sharded intern set, threads interning unrelated strings mostly take
+//! different locks, so throughput should scale with the thread count
+//! (up to the number of cores).
+//!
+//! Run with `cargo bench --bench contention`.
+
+use std::time::{Duration, Instant};
+
+use atom_str::Atom;
+
+const STRINGS_PER_THREAD: usize = 200_000;
+
+fn run(threads: usize) -> Duration {
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for thread in 0..threads {
+            scope.spawn(move || {
+                for i in 0..STRINGS_PER_THREAD {
+                    // Each thread interns its own strings, then re-interns
+                    // them, so both misses and hits are measured.
+                    let string = format!("bench {threads} {thread} {}", i % (STRINGS_PER_THREAD / 2));
+                    std::hint::black_box(Atom::new(&string));
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn main() {
+    let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+    println!("{cores} cores available");
+    for threads in [1, 2, 4, 8, 16] {
+        let elapsed = run(threads);
+        let total = threads * STRINGS_PER_THREAD;
+        let per_second = total as f64 / elapsed.as_secs_f64();
+        println!("{threads:>2} threads: {total:>8} interns in {elapsed:>10.2?} ({per_second:>12.0} interns/s)");
+    }
+}
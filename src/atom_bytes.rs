@@ -0,0 +1,222 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    hash::Hasher,
+    ptr::NonNull,
+    sync::{
+        LazyLock,
+        Mutex,
+    },
+};
+use crate::{
+    Atom,
+    AtomInner,
+    AtomKey,
+    InternSet,
+};
+
+/// The set of interned byte strings. The atoms in this set may hold
+/// non-UTF-8 bytes, so they must never be handed out as [Atom]s.
+static BYTES_INTERN_SET: LazyLock<Mutex<InternSet>> = LazyLock::new(|| Mutex::new(InternSet::default()));
+
+/// An interned byte string, the binary counterpart of [Atom].
+///
+/// Like [Atom], an [AtomBytes] is a cheaply copyable pointer, and two
+/// [AtomBytes] are equal if and only if their bytes are equal. Byte
+/// strings are interned into their own global set, separate from the one
+/// used by [Atom].
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct AtomBytes {
+    inner: NonNull<AtomInner<()>>,
+}
+
+unsafe impl Send for AtomBytes
+where AtomInner<()>: Send {}
+unsafe impl Sync for AtomBytes
+where AtomInner<()>: Sync {}
+
+impl AtomBytes {
+    /// Create a new interned [AtomBytes].
+    /// Ensures only one instance in memory.
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Self {
+        let key = AtomKey::from_bytes(bytes);
+        let mut set_lock = BYTES_INTERN_SET.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(atom) = set_lock.find_bytes(key, bytes) {
+            return Self::from_atom(atom);
+        }
+        let inner = AtomInner::alloc_new_bytes(bytes, key).expect("Out of memory or something.");
+        let atom = Atom { inner };
+        set_lock.insert(atom);
+        Self::from_atom(atom)
+    }
+
+    #[inline]
+    fn from_atom(atom: Atom) -> Self {
+        Self {
+            inner: atom.inner,
+        }
+    }
+
+    /// Returns the [AtomBytes]'s [AtomKey].
+    #[must_use]
+    #[inline]
+    pub fn key(self) -> AtomKey {
+        unsafe {
+            self.inner.as_ref().key()
+        }
+    }
+
+    /// Returns the number of bytes.
+    #[must_use]
+    #[inline]
+    pub fn len(self) -> usize {
+        self.key().len
+    }
+
+    /// Returns `true` if there are no bytes.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_bytes(self) -> &'static [u8] {
+        Atom { inner: self.inner }.raw_bytes()
+    }
+
+    /// Compares the pointers of two [AtomBytes] instances.
+    #[must_use]
+    #[inline]
+    pub fn ptr_eq(lhs: Self, rhs: Self) -> bool {
+        std::ptr::eq(lhs.inner.as_ptr(), rhs.inner.as_ptr())
+    }
+
+    /// Interns the bytes as an [Atom] if they are valid UTF-8.
+    #[must_use]
+    #[inline]
+    pub fn to_atom(self) -> Option<Atom> {
+        std::str::from_utf8(self.as_bytes()).ok().map(Atom::new)
+    }
+}
+
+impl std::cmp::PartialEq for AtomBytes {
+    fn eq(&self, other: &Self) -> bool {
+        AtomBytes::ptr_eq(*self, *other)
+    }
+}
+
+impl std::cmp::Eq for AtomBytes {}
+
+impl std::cmp::PartialOrd for AtomBytes {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::cmp::Ord for AtomBytes {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl std::cmp::PartialEq<[u8]> for AtomBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes().eq(other)
+    }
+}
+
+impl std::cmp::PartialEq<&[u8]> for AtomBytes {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.as_bytes().eq(*other)
+    }
+}
+
+impl std::hash::Hash for AtomBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash the content to uphold the `Borrow<[u8]>` contract.
+        self.as_bytes().hash(state);
+    }
+}
+
+impl std::ops::Deref for AtomBytes {
+    type Target = [u8];
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for AtomBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl std::borrow::Borrow<[u8]> for AtomBytes {
+    fn borrow(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl From<&[u8]> for AtomBytes {
+    #[inline]
+    fn from(value: &[u8]) -> Self {
+        AtomBytes::new(value)
+    }
+}
+
+impl From<Atom> for AtomBytes {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        AtomBytes::new(value.as_bytes())
+    }
+}
+
+impl TryFrom<AtomBytes> for Atom {
+    type Error = std::str::Utf8Error;
+    #[inline]
+    fn try_from(value: AtomBytes) -> Result<Self, Self::Error> {
+        std::str::from_utf8(value.as_bytes()).map(Atom::new)
+    }
+}
+
+impl std::fmt::Debug for AtomBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atom_bytes_test() {
+        let blob = AtomBytes::new(b"#bytes \xff\x00");
+        assert!(AtomBytes::ptr_eq(blob, AtomBytes::new(b"#bytes \xff\x00")));
+        assert_ne!(blob, AtomBytes::new(b"#bytes \xfe\x00"));
+        assert_eq!(blob.as_bytes(), b"#bytes \xff\x00");
+        assert_eq!(blob.len(), 9);
+        assert_eq!(blob.to_atom(), None);
+        assert!(Atom::try_from(blob).is_err());
+        assert!(AtomBytes::new(b"").is_empty());
+    }
+
+    #[test]
+    fn atom_conversion_test() {
+        let atom = Atom::new("#bytes utf8");
+        let bytes = AtomBytes::from(atom);
+        assert!(AtomBytes::ptr_eq(bytes, AtomBytes::new(b"#bytes utf8")));
+        assert_eq!(bytes.to_atom(), Some(atom));
+        assert_eq!(Atom::try_from(bytes), Ok(atom));
+        // The byte set is separate from the string set.
+        assert_ne!(bytes.as_bytes().as_ptr(), atom.as_bytes().as_ptr());
+    }
+}
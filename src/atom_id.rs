@@ -0,0 +1,201 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::Ordering,
+        LazyLock,
+        RwLock,
+    },
+};
+use crate::{
+    lock_shard,
+    Atom,
+    INSERT_SEQ,
+    SHARD_COUNT,
+};
+
+/// The atoms that have been assigned an [AtomId], indexed by id. The
+/// slots of deallocated atoms are cleared.
+#[derive(Default)]
+struct AtomIdTable {
+    atoms: Vec<Option<Atom>>,
+    /// The id of each atom in `atoms`, by [Atom::addr].
+    ids: HashMap<usize, u32>,
+}
+
+impl AtomIdTable {
+    fn assign(&mut self, atom: Atom) -> AtomId {
+        if let Some(&id) = self.ids.get(&atom.addr()) {
+            return AtomId(id);
+        }
+        let id = u32::try_from(self.atoms.len()).expect("AtomId space exhausted");
+        self.atoms.push(Some(atom));
+        self.ids.insert(atom.addr(), id);
+        AtomId(id)
+    }
+}
+
+static ATOM_ID_TABLE: LazyLock<RwLock<AtomIdTable>> = LazyLock::new(RwLock::default);
+
+/// A compact 4-byte handle to an [Atom], for storing large numbers of
+/// atoms. Returned by [Atom::id].
+/// 
+/// Ids are assigned in order, starting from `0`, the first time an [Atom]
+/// is passed to [Atom::id], so atoms that never need an id cost nothing.
+/// An id remains valid for the rest of the program, unless its atom is
+/// freed by [Atom::deallocate_many].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AtomId(u32);
+
+impl AtomId {
+    /// Returns the [Atom] with this id.
+    /// 
+    /// # Panics
+    /// Panics if the [Atom] was freed by [Atom::deallocate_many].
+    #[must_use]
+    pub fn resolve(self) -> Atom {
+        let table = ATOM_ID_TABLE.read().unwrap_or_else(|err| err.into_inner());
+        table.atoms[self.0 as usize].expect("AtomId of a deallocated Atom")
+    }
+
+    /// Returns the string of the [Atom] with this id.
+    #[must_use]
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        self.resolve().as_str()
+    }
+
+    /// Returns the id as a [u32].
+    #[must_use]
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the [AtomId] for `id`, or [None] if no live atom has that
+    /// id.
+    #[must_use]
+    pub fn from_u32(id: u32) -> Option<Self> {
+        let table = ATOM_ID_TABLE.read().unwrap_or_else(|err| err.into_inner());
+        table.atoms.get(id as usize)?.map(|_| AtomId(id))
+    }
+}
+
+impl From<AtomId> for Atom {
+    #[inline]
+    fn from(value: AtomId) -> Self {
+        value.resolve()
+    }
+}
+
+impl std::fmt::Display for AtomId {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A point in the sequence of atoms interned into the global set, taken
+/// by [Atom::marker].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AtomMarker(u64);
+
+/// Clears the table slots of atoms that are about to be deallocated.
+pub(crate) fn forget(atoms: &[Atom]) {
+    let mut table = ATOM_ID_TABLE.write().unwrap_or_else(|err| err.into_inner());
+    for atom in atoms {
+        if let Some(id) = table.ids.remove(&atom.addr()) {
+            table.atoms[id as usize] = None;
+        }
+    }
+}
+
+impl Atom {
+    /// Returns the [AtomId] of the [Atom], assigning the next id if the
+    /// [Atom] doesn't have one yet.
+    /// 
+    /// # Panics
+    /// Panics if more than [u32::MAX] atoms are assigned ids.
+    #[must_use]
+    pub fn id(self) -> AtomId {
+        {
+            let table = ATOM_ID_TABLE.read().unwrap_or_else(|err| err.into_inner());
+            if let Some(&id) = table.ids.get(&self.addr()) {
+                return AtomId(id);
+            }
+        }
+        // Another thread may assign an id before the write lock is taken,
+        // so `assign` checks again.
+        ATOM_ID_TABLE.write().unwrap_or_else(|err| err.into_inner()).assign(self)
+    }
+
+    /// Returns an [AtomMarker] for the current point in the sequence of
+    /// interned atoms, to be passed to [Atom::since] later.
+    #[must_use]
+    pub fn marker() -> AtomMarker {
+        AtomMarker(INSERT_SEQ.load(Ordering::Relaxed))
+    }
+
+    /// Returns the atoms interned into the global set after `marker` was
+    /// taken, in the order they were interned. Atoms outside the global
+    /// set, such as those from [Atom::new_unique] or
+    /// [Atom::new_in_namespace], are never included, and atoms freed by
+    /// [Atom::deallocate_many] are dropped from the sequence.
+    /// 
+    /// Each shard of the intern set logs its atoms in sequence for this,
+    /// which costs 16 bytes per interned [Atom].
+    #[must_use]
+    pub fn since(marker: AtomMarker) -> Vec<Atom> {
+        let mut logged: Vec<(u64, Atom)> = (0..SHARD_COUNT)
+            .flat_map(|index| lock_shard(index).logged_since(marker.0).to_vec())
+            .collect();
+        logged.sort_unstable_by_key(|&(seq, _)| seq);
+        logged.into_iter().map(|(_, atom)| atom).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atom_id_test() {
+        let a = Atom::new("#atom id a");
+        let b = Atom::new("#atom id b");
+        let a_id = a.id();
+        let b_id = b.id();
+        assert_ne!(a_id, b_id);
+        assert!(a_id < b_id);
+        assert_eq!(a.id(), a_id);
+        assert_eq!(Atom::new("#atom id a").id(), a_id);
+        assert!(Atom::ptr_eq(a_id.resolve(), a));
+        assert_eq!(b_id.as_str(), "#atom id b");
+        assert_eq!(AtomId::from_u32(b_id.as_u32()), Some(b_id));
+        assert_eq!(AtomId::from_u32(u32::MAX), None);
+        assert_eq!(std::mem::size_of::<AtomId>(), 4);
+    }
+
+    #[test]
+    fn since_test() {
+        let _ = Atom::new("#since old");
+        let marker = Atom::marker();
+        let new = ["#since a", "#since b", "#since c"].map(Atom::new);
+        let _ = Atom::new("#since old");
+        let _ = Atom::new("#since b");
+        // Other tests may intern atoms concurrently.
+        let since: Vec<Atom> = Atom::since(marker).into_iter()
+            .filter(|atom| atom.starts_with("#since"))
+            .collect();
+        assert_eq!(since, new);
+        let marker = Atom::marker();
+        let unique = Atom::new_unique("#since unique");
+        let _ = unique.id();
+        let namespaced = Atom::new_in_namespace(7, "#since namespaced");
+        let since = Atom::since(marker);
+        assert!(!since.iter().any(|&atom| Atom::ptr_eq(atom, unique)));
+        assert!(!since.iter().any(|&atom| Atom::ptr_eq(atom, namespaced)));
+    }
+}
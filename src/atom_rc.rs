@@ -0,0 +1,161 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    collections::HashMap,
+    hash::Hasher,
+    sync::{
+        Arc,
+        LazyLock,
+        Mutex,
+        Weak,
+    },
+};
+
+type Finalizer = Box<dyn FnOnce() + Send>;
+
+/// The live reference-counted atoms, by string. Entries are removed when
+/// their atom is collected.
+static RC_SET: LazyLock<Mutex<HashMap<Arc<str>, Weak<RcInner>>>> = LazyLock::new(Mutex::default);
+
+struct RcInner {
+    string: Arc<str>,
+    finalizers: Mutex<Vec<Finalizer>>,
+}
+
+impl Drop for RcInner {
+    fn drop(&mut self) {
+        {
+            let mut set_lock = RC_SET.lock().unwrap_or_else(|err| err.into_inner());
+            // The string may have been interned again since the last
+            // reference was dropped, in which case the entry is a new atom's.
+            if set_lock.get(&self.string).is_some_and(|weak| std::ptr::eq(weak.as_ptr(), self)) {
+                set_lock.remove(&self.string);
+            }
+        }
+        // Finalizers run after the lock is released, so they may intern.
+        let finalizers = self.finalizers.get_mut().unwrap_or_else(|err| err.into_inner());
+        let finalizers = std::mem::take(finalizers);
+        for finalizer in finalizers {
+            finalizer();
+        }
+    }
+}
+
+/// A reference-counted interned string.
+/// 
+/// Unlike [Atom](crate::Atom), which keeps every string forever, an
+/// [AtomRc] is collected when its last reference is dropped. While any
+/// reference remains, interning the same string returns the same
+/// allocation; after that, interning it again creates a new one.
+#[derive(Clone)]
+pub struct AtomRc(Arc<RcInner>);
+
+impl AtomRc {
+    /// Create a new interned [AtomRc] string.
+    #[must_use]
+    pub fn new(string: &str) -> Self {
+        let mut set_lock = RC_SET.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(atom) = set_lock.get(string).and_then(Weak::upgrade) {
+            return AtomRc(atom);
+        }
+        let string: Arc<str> = Arc::from(string);
+        let inner = Arc::new(RcInner {
+            string: string.clone(),
+            finalizers: Mutex::default(),
+        });
+        set_lock.insert(string, Arc::downgrade(&inner));
+        AtomRc(inner)
+    }
+
+    /// Create a new interned [AtomRc] string, registering `finalizer` to run
+    /// once the [AtomRc] is collected, after its last reference is dropped.
+    /// 
+    /// If the string is already interned, `finalizer` is added to those of
+    /// the existing [AtomRc]. Finalizers run on the thread that drops the
+    /// last reference, without holding any lock, so they may intern strings.
+    #[must_use]
+    pub fn new_with_finalizer(string: &str, finalizer: impl FnOnce() + Send + 'static) -> Self {
+        let atom = AtomRc::new(string);
+        atom.0.finalizers.lock().unwrap_or_else(|err| err.into_inner()).push(Box::new(finalizer));
+        atom
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0.string
+    }
+
+    /// Compares the pointers of two [AtomRc] instances.
+    #[must_use]
+    #[inline]
+    pub fn ptr_eq(lhs: &Self, rhs: &Self) -> bool {
+        Arc::ptr_eq(&lhs.0, &rhs.0)
+    }
+}
+
+impl std::cmp::PartialEq for AtomRc {
+    fn eq(&self, other: &Self) -> bool {
+        AtomRc::ptr_eq(self, other)
+    }
+}
+
+impl std::cmp::Eq for AtomRc {}
+
+impl std::hash::Hash for AtomRc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl std::borrow::Borrow<str> for AtomRc {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl_str_handle!(AtomRc);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    #[test]
+    fn intern_test() {
+        let a = AtomRc::new("#rc intern");
+        let b = AtomRc::new("#rc intern");
+        assert!(AtomRc::ptr_eq(&a, &b));
+        assert_eq!(a, "#rc intern");
+        assert_ne!(a, AtomRc::new("#rc other"));
+    }
+
+    #[test]
+    fn finalizer_test() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let finalizer = {
+            let runs = runs.clone();
+            move || {
+                // Interning from a finalizer must not deadlock.
+                let _ = AtomRc::new("#rc finalizer reentrant");
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        };
+        let a = AtomRc::new_with_finalizer("#rc finalizer", finalizer);
+        let b = AtomRc::new("#rc finalizer");
+        let c = b.clone();
+        drop(a);
+        drop(b);
+        assert_eq!(runs.load(Ordering::Relaxed), 0);
+        drop(c);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        // The collected string is interned afresh, without the finalizer.
+        drop(AtomRc::new("#rc finalizer"));
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+    }
+}
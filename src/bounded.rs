@@ -0,0 +1,194 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    hash::Hasher,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+/// An interner that holds at most a fixed number of strings, evicting the
+/// least recently used string when it is full.
+/// 
+/// Unlike the global set behind [Atom](crate::Atom), which keeps every
+/// string forever, a [BoundedInterner] frees evicted strings. Strings are
+/// therefore returned as reference-counted [BoundedAtom]s rather than
+/// `'static` atoms: an evicted string stays alive until its last
+/// [BoundedAtom] is dropped, but interning it again creates a new
+/// allocation.
+#[derive(Debug)]
+pub struct BoundedInterner {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    /// The interned strings, with the tick of their last use.
+    strings: HashMap<Arc<str>, u64>,
+    /// The interned strings ordered by the tick of their last use.
+    order: BTreeMap<u64, Arc<str>>,
+    tick: u64,
+}
+
+impl BoundedInterner {
+    /// Creates an empty [BoundedInterner] that holds at most `capacity`
+    /// strings.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Interns `string` into this [BoundedInterner], marking it as the most
+    /// recently used string. If the interner is full and `string` isn't
+    /// in it, the least recently used string is evicted first.
+    #[must_use]
+    pub fn intern(&self, string: &str) -> BoundedAtom {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let state = &mut *state;
+        state.tick += 1;
+        let tick = state.tick;
+        if let Some((shared, &last_use)) = state.strings.get_key_value(string) {
+            let shared = shared.clone();
+            state.order.remove(&last_use);
+            state.order.insert(tick, shared.clone());
+            state.strings.insert(shared.clone(), tick);
+            return BoundedAtom(shared);
+        }
+        let shared: Arc<str> = Arc::from(string);
+        if self.capacity == 0 {
+            return BoundedAtom(shared);
+        }
+        if state.strings.len() >= self.capacity
+            && let Some((_, evicted)) = state.order.pop_first() {
+            state.strings.remove(&evicted);
+        }
+        state.order.insert(tick, shared.clone());
+        state.strings.insert(shared.clone(), tick);
+        BoundedAtom(shared)
+    }
+
+    /// Returns `true` if `string` is currently held by this
+    /// [BoundedInterner]. This doesn't count as a use of `string`.
+    #[must_use]
+    pub fn contains(&self, string: &str) -> bool {
+        self.state.lock().unwrap_or_else(|err| err.into_inner()).strings.contains_key(string)
+    }
+
+    /// Returns the number of strings currently held.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap_or_else(|err| err.into_inner()).strings.len()
+    }
+
+    /// Returns `true` if no strings are held.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of strings held at once.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// A string interned into a [BoundedInterner].
+/// 
+/// Two [BoundedAtom]s from the same [BoundedInterner] are [BoundedAtom::ptr_eq]
+/// if the string wasn't evicted in between. Equality compares the strings.
+#[derive(Clone)]
+pub struct BoundedAtom(Arc<str>);
+
+impl BoundedAtom {
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Compares the pointers of two [BoundedAtom] instances.
+    #[must_use]
+    #[inline]
+    pub fn ptr_eq(lhs: &Self, rhs: &Self) -> bool {
+        Arc::ptr_eq(&lhs.0, &rhs.0)
+    }
+}
+
+impl std::cmp::PartialEq for BoundedAtom {
+    fn eq(&self, other: &Self) -> bool {
+        BoundedAtom::ptr_eq(self, other) || self.as_str() == other.as_str()
+    }
+}
+
+impl std::cmp::Eq for BoundedAtom {}
+
+impl std::hash::Hash for BoundedAtom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl std::borrow::Borrow<str> for BoundedAtom {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl_str_handle!(BoundedAtom);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_test() {
+        let interner = BoundedInterner::with_capacity(4);
+        let a = interner.intern("a");
+        assert!(BoundedAtom::ptr_eq(&a, &interner.intern("a")));
+        assert_eq!(a, "a");
+        assert_eq!(interner.len(), 1);
+        assert_eq!(interner.capacity(), 4);
+    }
+
+    #[test]
+    fn eviction_test() {
+        let interner = BoundedInterner::with_capacity(2);
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+        let _ = interner.intern("a");
+        let _ = interner.intern("c");
+        assert_eq!(interner.len(), 2);
+        assert!(interner.contains("a"));
+        assert!(!interner.contains("b"));
+        assert!(interner.contains("c"));
+        // The evicted string lives on through its handle.
+        assert_eq!(b, "b");
+        let b_again = interner.intern("b");
+        assert!(!BoundedAtom::ptr_eq(&b, &b_again));
+        assert_eq!(b, b_again);
+        assert!(!interner.contains("a"));
+        assert_eq!(a, "a");
+    }
+
+    #[test]
+    fn zero_capacity_test() {
+        let interner = BoundedInterner::with_capacity(0);
+        assert_eq!(interner.intern("a"), "a");
+        assert!(interner.is_empty());
+    }
+}
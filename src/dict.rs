@@ -0,0 +1,185 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+//! Dictionary encoding for serializing many repeated [Atom]s.
+//! 
+//! Each distinct string is written once into a string table, and every
+//! [Atom] is written as a `u32` index into that table. When deserializing,
+//! the table is read back and each entry is interned once.
+
+use std::collections::HashMap;
+use serde::{
+    de::{
+        Error,
+        SeqAccess,
+        Visitor,
+    },
+    ser::{
+        SerializeSeq,
+        SerializeStruct,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use crate::Atom;
+
+/// Serializer state that assigns each distinct [Atom] an index into a
+/// string table.
+/// 
+/// Serializing the encoder writes the string table.
+#[derive(Debug, Default, Clone)]
+pub struct AtomDictEncoder {
+    table: Vec<Atom>,
+    indices: HashMap<Atom, u32>,
+}
+
+impl AtomDictEncoder {
+    /// Creates an empty encoder.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the table index for `atom`, adding it to the table if this
+    /// is its first occurrence.
+    /// 
+    /// # Panics
+    /// Panics if the table would hold more than [u32::MAX] entries.
+    pub fn encode(&mut self, atom: Atom) -> u32 {
+        *self.indices.entry(atom).or_insert_with(|| {
+            let index = u32::try_from(self.table.len()).expect("Atom dictionary table is full.");
+            self.table.push(atom);
+            index
+        })
+    }
+
+    /// Returns the string table in index order.
+    #[must_use]
+    #[inline]
+    pub fn table(&self) -> &[Atom] {
+        &self.table
+    }
+}
+
+impl Serialize for AtomDictEncoder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.table.len()))?;
+        for atom in self.table.iter() {
+            seq.serialize_element(atom.as_str())?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializer state holding a string table read by [AtomDictEncoder]'s
+/// [Serialize] implementation, with every entry interned.
+#[derive(Debug, Default, Clone)]
+pub struct AtomDictDecoder {
+    table: Vec<Atom>,
+}
+
+impl AtomDictDecoder {
+    /// Returns the [Atom] at `index` in the table, or [None] if `index` is
+    /// out of bounds.
+    #[must_use]
+    #[inline]
+    pub fn decode(&self, index: u32) -> Option<Atom> {
+        self.table.get(index as usize).copied()
+    }
+
+    /// Returns the string table in index order.
+    #[must_use]
+    #[inline]
+    pub fn table(&self) -> &[Atom] {
+        &self.table
+    }
+}
+
+impl<'de> Deserialize<'de> for AtomDictDecoder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TableVisitor;
+
+        impl<'de> Visitor<'de> for TableVisitor {
+            type Value = AtomDictDecoder;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a sequence of strings")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                // The size hint comes from untrusted input, so it is capped
+                // to keep a bogus length from allocating a huge table.
+                let mut table = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(atom) = seq.next_element()? {
+                    table.push(atom);
+                }
+                Ok(AtomDictDecoder { table })
+            }
+        }
+
+        deserializer.deserialize_seq(TableVisitor)
+    }
+}
+
+/// A sequence of [Atom]s that serializes as a string table of the distinct
+/// strings plus a `u32` index per atom.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DictEncoded(pub Vec<Atom>);
+
+impl Serialize for DictEncoded {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut encoder = AtomDictEncoder::new();
+        let indices: Vec<u32> = self.0.iter().map(|&atom| encoder.encode(atom)).collect();
+        let mut state = serializer.serialize_struct("DictEncoded", 2)?;
+        state.serialize_field("table", &encoder)?;
+        state.serialize_field("indices", &indices)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for DictEncoded {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "DictEncoded")]
+        struct Repr {
+            table: AtomDictDecoder,
+            indices: Vec<u32>,
+        }
+
+        let Repr { table, indices } = Repr::deserialize(deserializer)?;
+        indices.into_iter()
+            .map(|index| table.decode(index).ok_or_else(|| {
+                D::Error::custom(format_args!("atom index {index} is out of bounds"))
+            }))
+            .collect::<Result<_, _>>()
+            .map(DictEncoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dict_round_trip_test() {
+        let words = ["#dict alpha", "#dict beta", "#dict gamma"];
+        let atoms: Vec<Atom> = (0..300).map(|i| Atom::new(words[i % words.len()])).collect();
+        let encoded = serde_json::to_string(&DictEncoded(atoms.clone())).unwrap();
+        let plain = serde_json::to_string(&atoms.iter().map(|atom| atom.as_str()).collect::<Vec<_>>()).unwrap();
+        assert!(encoded.len() * 3 < plain.len());
+
+        let DictEncoded(decoded) = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, atoms);
+        assert!(Atom::ptr_eq(decoded[0], decoded[3]));
+    }
+
+    #[test]
+    fn dict_bad_index_test() {
+        let result = serde_json::from_str::<DictEncoded>(r#"{"table":["a"],"indices":[0,1]}"#);
+        assert!(result.is_err());
+    }
+}
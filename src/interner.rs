@@ -0,0 +1,275 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    hash::Hasher,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::Mutex,
+};
+use crate::{
+    Atom,
+    AtomInner,
+    AtomKey,
+    HashMode,
+    InternSet,
+};
+
+/// A non-global set of interned strings that frees all of its strings
+/// when dropped.
+/// 
+/// Strings interned into an [Interner] are returned as [LocalAtom]s that
+/// borrow the [Interner], rather than as `'static` [Atom]s. Each
+/// [Interner] deduplicates independently of the global intern set and of
+/// other interners.
+#[derive(Default)]
+pub struct Interner {
+    /// The atoms in this set are owned by the [Interner] and must never be
+    /// handed out as [Atom]s.
+    set: Mutex<InternSet>,
+    mode: HashMode,
+}
+
+impl Interner {
+    /// Creates an empty [Interner].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty [Interner] that hashes strings according to `mode`.
+    /// 
+    /// [HashMode::Full] keeps buckets short for long strings that share
+    /// their first and last bytes, at the cost of hashing every byte.
+    #[must_use]
+    #[inline]
+    pub fn with_hash_mode(mode: HashMode) -> Self {
+        Self {
+            set: Mutex::default(),
+            mode,
+        }
+    }
+
+    /// Returns the [HashMode] of this [Interner].
+    #[must_use]
+    #[inline]
+    pub fn hash_mode(&self) -> HashMode {
+        self.mode
+    }
+
+    /// Interns `string` into this [Interner].
+    /// Ensures only one instance in this [Interner].
+    #[must_use]
+    pub fn intern(&self, string: &str) -> LocalAtom<'_> {
+        let key = AtomKey::from_str_with(string, self.mode);
+        let mut set_lock = self.set.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(atom) = set_lock.find(key, string) {
+            return LocalAtom::from_atom(atom);
+        }
+        let atom = Atom::new_internal(string, key);
+        set_lock.insert(atom);
+        LocalAtom::from_atom(atom)
+    }
+
+    /// Returns the [LocalAtom] for `string` if it has been interned into
+    /// this [Interner], without interning it otherwise.
+    #[must_use]
+    pub fn get(&self, string: &str) -> Option<LocalAtom<'_>> {
+        let key = AtomKey::from_str_with(string, self.mode);
+        let set_lock = self.set.lock().unwrap_or_else(|err| err.into_inner());
+        set_lock.find(key, string).map(LocalAtom::from_atom)
+    }
+
+    /// Returns a snapshot of every [LocalAtom] in this [Interner].
+    #[must_use]
+    pub fn atoms(&self) -> Vec<LocalAtom<'_>> {
+        let set_lock = self.set.lock().unwrap_or_else(|err| err.into_inner());
+        set_lock.atoms().map(LocalAtom::from_atom).collect()
+    }
+
+    /// Returns the number of strings interned into this [Interner].
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let set_lock = self.set.lock().unwrap_or_else(|err| err.into_inner());
+        set_lock.atoms().count()
+    }
+
+    /// Returns `true` if no strings have been interned into this
+    /// [Interner].
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for Interner {
+    fn drop(&mut self) {
+        let set = self.set.get_mut().unwrap_or_else(|err| err.into_inner());
+        for atom in set.atoms() {
+            unsafe {
+                // Every atom in the set was allocated by `intern`, and no
+                // `LocalAtom` can outlive `self`.
+                AtomInner::dealloc(atom.inner);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Interner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.atoms()).finish()
+    }
+}
+
+/// A string interned into an [Interner]. A [LocalAtom] can't outlive its
+/// [Interner].
+/// 
+/// Like [Atom], a [LocalAtom] is a cheaply copyable pointer, and two
+/// [LocalAtom]s from the same [Interner] are equal if and only if they
+/// point to the same string.
+#[derive(Clone, Copy)]
+pub struct LocalAtom<'a> {
+    inner: NonNull<AtomInner<()>>,
+    _interner: PhantomData<&'a Interner>,
+}
+
+unsafe impl Send for LocalAtom<'_>
+where AtomInner<()>: Send {}
+unsafe impl Sync for LocalAtom<'_>
+where AtomInner<()>: Sync {}
+
+impl<'a> LocalAtom<'a> {
+    #[inline]
+    fn from_atom(atom: Atom) -> Self {
+        Self {
+            inner: atom.inner,
+            _interner: PhantomData,
+        }
+    }
+
+    /// Views the [LocalAtom] as an [Atom] for the accessors the two share.
+    /// The result must not escape, since it claims a `'static` lifetime.
+    #[inline]
+    fn as_atom(self) -> Atom {
+        Atom {
+            inner: self.inner,
+        }
+    }
+
+    /// Returns the [LocalAtom]'s [AtomKey].
+    #[must_use]
+    #[inline]
+    pub fn key(self) -> AtomKey {
+        self.as_atom().key()
+    }
+
+    /// Returns the length of the string.
+    #[must_use]
+    #[inline]
+    pub fn len(self) -> usize {
+        self.as_atom().len()
+    }
+
+    /// Returns `true` if the string is empty.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_str(self) -> &'a str {
+        self.as_atom().as_str()
+    }
+
+    /// Compares the pointers of two [LocalAtom] instances.
+    #[must_use]
+    #[inline]
+    pub fn ptr_eq(lhs: Self, rhs: Self) -> bool {
+        std::ptr::eq(lhs.inner.as_ptr(), rhs.inner.as_ptr())
+    }
+
+    /// Interns the string into the global intern set.
+    #[must_use]
+    #[inline]
+    pub fn to_global(self) -> Atom {
+        Atom::new(self.as_str())
+    }
+}
+
+impl std::cmp::PartialEq for LocalAtom<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        LocalAtom::ptr_eq(*self, *other)
+    }
+}
+
+impl std::cmp::Eq for LocalAtom<'_> {}
+
+impl std::hash::Hash for LocalAtom<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl_str_handle!(LocalAtom<'_>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_mode_test() {
+        let head = "h".repeat(64);
+        let tail = "t".repeat(64);
+        let middle_a = format!("{head}a{tail}");
+        let middle_b = format!("{head}b{tail}");
+        let ends = Interner::with_hash_mode(HashMode::Ends);
+        assert_eq!(ends.intern(&middle_a).key(), ends.intern(&middle_b).key());
+        let full = Interner::with_hash_mode(HashMode::Full);
+        assert_eq!(full.hash_mode(), HashMode::Full);
+        let a = full.intern(&middle_a);
+        let b = full.intern(&middle_b);
+        assert_ne!(a.key(), b.key());
+        assert!(LocalAtom::ptr_eq(a, full.intern(&middle_a)));
+        assert_eq!(full.get(&middle_b), Some(b));
+    }
+
+    #[test]
+    fn intern_test() {
+        let interner = Interner::new();
+        let a = interner.intern("#interner a");
+        let b = interner.intern("#interner b");
+        assert!(LocalAtom::ptr_eq(a, interner.intern("#interner a")));
+        assert_ne!(a, b);
+        assert_eq!(a, "#interner a");
+        assert_eq!(interner.get("#interner b"), Some(b));
+        assert_eq!(interner.get("#interner c"), None);
+        assert!(Atom::ptr_eq(a.to_global(), Atom::new("#interner a")));
+        assert_ne!(a.as_ptr(), Atom::new("#interner a").as_ptr());
+    }
+
+    #[test]
+    fn separate_interners_test() {
+        let first = Interner::new();
+        let second = Interner::new();
+        let a = first.intern("#interner shared");
+        let b = second.intern("#interner shared");
+        assert!(!std::ptr::eq(a.as_ptr(), b.as_ptr()));
+        assert_eq!(a.as_str(), b.as_str());
+    }
+
+    #[test]
+    fn atoms_test() {
+        let interner = Interner::new();
+        assert!(interner.is_empty());
+        let words = ["#interner x", "#interner y", "#interner x", ""];
+        let atoms: Vec<LocalAtom> = words.iter().map(|word| interner.intern(word)).collect();
+        let mut all = interner.atoms();
+        all.sort();
+        assert_eq!(all, [atoms[3], atoms[0], atoms[1]]);
+        assert_eq!(interner.len(), 3);
+    }
+}
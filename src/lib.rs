@@ -5,23 +5,291 @@
 use std::{
     alloc::{
         alloc,
+        dealloc,
         Layout,
-    }, borrow::Cow, collections::HashMap, hash::Hasher, path::{
+    }, borrow::Cow, cell::RefCell, collections::HashMap, hash::Hasher, io::{
+        Read,
+        Write,
+    }, path::{
         Path,
         PathBuf
-    }, ptr::NonNull, rc::Rc, sync::{
+    }, ops::Range, ptr::NonNull, rc::Rc, sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
         Arc,
         LazyLock,
         Mutex,
+        MutexGuard,
     }
 };
 use twox_hash::XxHash64;
 
+/// Implements the string-like traits shared by the handle types of the
+/// other interners ([AtomRc], [BoundedAtom] and [LocalAtom]) in terms of
+/// their `as_str`: ordering and comparison with strings, `Deref`,
+/// `AsRef<str>`, `Display` and `Debug`. Equality between handles and
+/// hashing differ between the types, so each implements those itself.
+macro_rules! impl_str_handle {
+    ($ty:ty) => {
+        impl std::cmp::PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl std::cmp::Ord for $ty {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+
+        impl std::cmp::PartialEq<str> for $ty {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str().eq(other)
+            }
+        }
+
+        impl std::cmp::PartialEq<&str> for $ty {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str().eq(*other)
+            }
+        }
+
+        impl std::ops::Deref for $ty {
+            type Target = str;
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                self.as_str()
+            }
+        }
+
+        impl AsRef<str> for $ty {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl std::fmt::Display for $ty {
+            #[inline]
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::fmt::Debug for $ty {
+            #[inline]
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{:?}", self.as_str())
+            }
+        }
+    };
+}
+
+mod atom_bytes;
+mod atom_id;
+mod atom_rc;
+mod bounded;
+#[cfg(feature = "serde")]
+mod dict;
+mod interner;
+mod lru_cache;
+mod static_interner;
+
+pub use atom_bytes::AtomBytes;
+pub use atom_id::{AtomId, AtomMarker};
+pub use atom_rc::AtomRc;
+pub use bounded::{BoundedAtom, BoundedInterner};
+#[cfg(feature = "serde")]
+pub use dict::{AtomDictDecoder, AtomDictEncoder, DictEncoded};
+pub use interner::{Interner, LocalAtom};
+pub use lru_cache::AtomLruCache;
+pub use static_interner::StaticInterner;
+
 const HASH_SEED: u64 = 0x9e3779b9;
 const ENDS_SIZE: usize = 64;
 
-/// The set of interned strings.
-static INTERN_SET: LazyLock<Mutex<HashMap<AtomKey, Vec<Atom>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+/// The number of shards of [INTERN_SET].
+const SHARD_COUNT: usize = 16;
+
+/// The set of interned strings, split into shards by [AtomKey] hash so
+/// that threads interning unrelated strings rarely contend for a lock.
+static INTERN_SET: LazyLock<[Mutex<InternSet>; SHARD_COUNT]> = LazyLock::new(|| std::array::from_fn(|_| Mutex::default()));
+
+/// The maximum number of atoms stored in a single [InternSet] bucket
+/// before further atoms with the same key overflow into the full-hash
+/// index. See [Atom::set_scan_budget].
+static SCAN_BUDGET: AtomicUsize = AtomicUsize::new(8);
+
+/// The intern sets of [Atom::new_in_namespace], by namespace.
+static NAMESPACE_SETS: LazyLock<Mutex<HashMap<u16, InternSet>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The atoms of [Atom::new_with], by their custom key. Atoms are only
+/// ever appended.
+static CUSTOM_KEY_SET: LazyLock<Mutex<HashMap<AtomKey, Vec<Atom>>>> = LazyLock::new(Mutex::default);
+
+/// The atoms of [Atom::new_ascii_ci], by the key of their ASCII-lowercased
+/// string. Atoms are only ever appended.
+static ASCII_CI_SET: LazyLock<Mutex<HashMap<AtomKey, Vec<Atom>>>> = LazyLock::new(Mutex::default);
+
+/// The soft limit on the bytes held by [INTERN_SET] for
+/// [Atom::try_intern_budgeted]. See [Atom::set_byte_budget].
+static BYTE_BUDGET: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// The sequence number of the next [Atom] interned into [INTERN_SET]. It
+/// is only advanced under a shard lock, so each shard's log is in order.
+/// See [Atom::since].
+static INSERT_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// The total length in bytes of the strings in [INTERN_SET], kept apart
+/// from the shards so that it can be read and reserved without locking
+/// them. See [Atom::total_interned_bytes].
+static INTERNED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Lock wait statistics for [INTERN_SET].
+#[cfg(feature = "instrument")]
+static LOCK_WAIT_TOTAL_NANOS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+#[cfg(feature = "instrument")]
+static LOCK_WAIT_MAX_NANOS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+#[cfg(feature = "instrument")]
+static LOCK_ACQUISITIONS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Locks the shard of [INTERN_SET] that holds the bucket for `key`.
+#[inline]
+fn lock_intern_set(key: AtomKey) -> MutexGuard<'static, InternSet> {
+    lock_shard((key.hash % SHARD_COUNT as u64) as usize)
+}
+
+/// Returns a snapshot of every [Atom] in [INTERN_SET], locking one shard
+/// at a time.
+fn snapshot_intern_set() -> Vec<Atom> {
+    (0..SHARD_COUNT)
+        .flat_map(|index| lock_shard(index).atoms().collect::<Vec<_>>())
+        .collect()
+}
+
+/// Locks the shard of [INTERN_SET] at `index`, recording the wait time
+/// with the `instrument` feature.
+/// 
+/// A poisoned shard is recovered rather than propagating the panic. The
+/// set is only mutated by single inserts and removals, so a panic while
+/// the lock is held (such as on allocation failure) can at worst leak an
+/// atom.
+fn lock_shard(index: usize) -> MutexGuard<'static, InternSet> {
+    #[cfg(feature = "instrument")]
+    let start = std::time::Instant::now();
+    let set_lock = INTERN_SET[index].lock().unwrap_or_else(|err| err.into_inner());
+    #[cfg(feature = "instrument")]
+    {
+        let waited = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        LOCK_WAIT_TOTAL_NANOS.fetch_add(waited, Ordering::Relaxed);
+        LOCK_WAIT_MAX_NANOS.fetch_max(waited, Ordering::Relaxed);
+        LOCK_ACQUISITIONS.fetch_add(1, Ordering::Relaxed);
+    }
+    set_lock
+}
+
+/// The interned single-byte ASCII atoms, indexed by byte. These are
+/// interned into [INTERN_SET] like any other atom, but [Atom::new] serves
+/// them from this table without locking or hashing.
+/// 
+/// The table is built on first use, so its atoms aren't recorded as new:
+/// they don't show up in [Atom::record_new_in] or [Atom::since].
+static ASCII_ATOMS: LazyLock<[Atom; 128]> = LazyLock::new(|| {
+    std::array::from_fn(|byte| {
+        let bytes = [byte as u8];
+        let string = std::str::from_utf8(&bytes).unwrap();
+        let key = AtomKey::from_str(string);
+        let mut set_lock = lock_intern_set(key);
+        // Paths that bypass the table may have interned the byte already.
+        if let Some(atom) = set_lock.find(key, string) {
+            return atom;
+        }
+        let atom = Atom::new_internal(string, key);
+        set_lock.insert(atom);
+        INTERNED_BYTES.fetch_add(atom.len(), Ordering::Relaxed);
+        atom
+    })
+});
+
+/// The empty atom. It is not stored in [INTERN_SET], so interning the
+/// empty string never locks or allocates.
+static EMPTY_ATOM_INNER: LazyLock<AtomInner<()>> = LazyLock::new(|| AtomInner {
+    key: AtomKey::from_str(""),
+    value: (),
+});
+
+thread_local! {
+    /// Stack of recorders for [Atom::record_new_in], innermost last.
+    static NEW_ATOM_RECORDERS: RefCell<Vec<Vec<Atom>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The number of [AtomInner] allocations made. See [Atom::alloc_count].
+#[cfg(feature = "count-allocs")]
+static ALLOC_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes `value` as an unsigned LEB128 varint.
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> std::io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Reads an unsigned LEB128 varint written by [write_varint].
+fn read_varint<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        let bits = u64::from(byte[0] & 0x7f);
+        if bits << shift >> shift != bits {
+            break;
+        }
+        value |= bits << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "varint overflows u64"))
+}
+
+/// Records a newly allocated [Atom] in the innermost active recorder.
+fn record_new_atom(atom: Atom) {
+    NEW_ATOM_RECORDERS.with_borrow_mut(|recorders| {
+        if let Some(recorder) = recorders.last_mut() {
+            recorder.push(atom);
+        }
+    });
+}
+
+/// Pops the innermost recorder when dropped, so that a panicking scope
+/// doesn't leave its recorder on the stack.
+struct RecorderGuard;
+
+impl RecorderGuard {
+    fn push() -> Self {
+        NEW_ATOM_RECORDERS.with_borrow_mut(|recorders| recorders.push(Vec::new()));
+        RecorderGuard
+    }
+
+    fn finish(self) -> Vec<Atom> {
+        std::mem::forget(self);
+        NEW_ATOM_RECORDERS.with_borrow_mut(|recorders| recorders.pop()).unwrap_or_default()
+    }
+}
+
+impl Drop for RecorderGuard {
+    fn drop(&mut self) {
+        NEW_ATOM_RECORDERS.with_borrow_mut(|recorders| recorders.pop());
+    }
+}
 
 /// Hash `bytes` with [XxHash64].
 #[must_use]
@@ -85,6 +353,66 @@ pub fn hash_str_ends(string: &str, end_size: usize) -> u64 {
     hash_bytes_ends(string.as_bytes(), end_size)
 }
 
+/// Interns `string` and returns the interned `'static` string.
+/// 
+/// This is the canonical way to deduplicate strings for code that stores
+/// `&'static str` rather than [Atom]. The returned slice shares storage
+/// with `Atom::new(string).as_str()`.
+#[must_use]
+#[inline]
+pub fn intern_static_str(string: &str) -> &'static str {
+    Atom::new(string).as_str()
+}
+
+/// Compares two strings in natural order, where runs of ASCII digits are
+/// compared by their numeric value, so that `"file2" < "file10"`.
+/// 
+/// Everything else is compared lexicographically. Digit runs with equal
+/// values are ordered by their number of leading zeros (fewest first).
+#[must_use]
+pub fn natural_cmp(lhs: &str, rhs: &str) -> std::cmp::Ordering {
+    fn split_digits(bytes: &[u8]) -> (&[u8], &[u8]) {
+        let len = bytes.iter().take_while(|byte| byte.is_ascii_digit()).count();
+        bytes.split_at(len)
+    }
+
+    fn trim_zeros(digits: &[u8]) -> &[u8] {
+        let zeros = digits.iter().take_while(|&&byte| byte == b'0').count();
+        &digits[zeros..]
+    }
+
+    let mut lhs = lhs.as_bytes();
+    let mut rhs = rhs.as_bytes();
+    loop {
+        match (lhs.first(), rhs.first()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let (lhs_digits, lhs_rest) = split_digits(lhs);
+                let (rhs_digits, rhs_rest) = split_digits(rhs);
+                let lhs_value = trim_zeros(lhs_digits);
+                let rhs_value = trim_zeros(rhs_digits);
+                let ordering = lhs_value.len().cmp(&rhs_value.len())
+                    .then_with(|| lhs_value.cmp(rhs_value))
+                    .then_with(|| lhs_digits.len().cmp(&rhs_digits.len()));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                lhs = lhs_rest;
+                rhs = rhs_rest;
+            }
+            (Some(l), Some(r)) => {
+                if l != r {
+                    return l.cmp(r);
+                }
+                lhs = &lhs[1..];
+                rhs = &rhs[1..];
+            }
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct AtomKey {
@@ -93,11 +421,38 @@ pub struct AtomKey {
 }
 
 impl AtomKey {
-    /// Creates an [AtomKey] from a string source.
+    /// Creates an [AtomKey] from a string source using [HashMode::GLOBAL].
+    #[allow(clippy::should_implement_trait)]
     #[must_use]
     #[inline]
     pub fn from_str(source: &str) -> AtomKey {
-        let hash = hash_str_ends(source, ENDS_SIZE);
+        Self::from_str_with(source, HashMode::GLOBAL)
+    }
+
+    /// Creates an [AtomKey] from a string source, hashing it according
+    /// to `mode`.
+    #[must_use]
+    #[inline]
+    pub fn from_str_with(source: &str, mode: HashMode) -> AtomKey {
+        Self::from_bytes_with(source.as_bytes(), mode)
+    }
+
+    /// Creates an [AtomKey] from a byte source using [HashMode::GLOBAL].
+    #[must_use]
+    #[inline]
+    pub fn from_bytes(source: &[u8]) -> AtomKey {
+        Self::from_bytes_with(source, HashMode::GLOBAL)
+    }
+
+    /// Creates an [AtomKey] from a byte source, hashing it according to
+    /// `mode`.
+    #[must_use]
+    #[inline]
+    pub fn from_bytes_with(source: &[u8], mode: HashMode) -> AtomKey {
+        let hash = match mode {
+            HashMode::Ends => hash_bytes_ends(source, ENDS_SIZE),
+            HashMode::Full => hash_bytes(source),
+        };
         let len = source.len();
         AtomKey {
             hash,
@@ -106,8 +461,49 @@ impl AtomKey {
     }
 }
 
+/// How much of a string is hashed into its [AtomKey].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashMode {
+    /// Hash only the first and last 64 bytes of the string. Long strings
+    /// that differ only in the middle share a key, and therefore a bucket.
+    #[default]
+    Ends,
+    /// Hash the entire string.
+    Full,
+}
+
+impl HashMode {
+    /// The [HashMode] of the global intern set: [HashMode::Full] with the
+    /// `full-hash` feature, otherwise [HashMode::Ends].
+    pub const GLOBAL: HashMode = if cfg!(feature = "full-hash") {
+        HashMode::Full
+    } else {
+        HashMode::Ends
+    };
+}
+
+/// Set in the stored `len` of an [AtomInner]'s key when its string bytes
+/// live outside the allocation, in which case `value` is an
+/// [ExternalBytes]. String lengths never exceed [isize::MAX], so the bit
+/// is otherwise always clear.
+const EXTERNAL_LEN_BIT: usize = 1 << (usize::BITS - 1);
+
+/// The `value` of an [AtomInner] whose string bytes live outside its
+/// allocation.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ExternalBytes {
+    ptr: NonNull<u8>,
+    /// The capacity of the leaked [String] buffer that `ptr` points into
+    /// (see [Atom::from_string]), or `0` if the bytes are a borrowed
+    /// `'static` string (see [Atom::new_static]).
+    capacity: usize,
+}
+
 #[repr(C)]
 struct AtomInner<T: ?Sized> {
+    /// The key, with [EXTERNAL_LEN_BIT] set in `len` if the bytes are
+    /// external. Read it with [AtomInner::key].
     key: AtomKey,
     value: T,
 }
@@ -122,19 +518,68 @@ impl AtomInner<()> {
     
     /// Gets the layout for [AtomInner<str>] with `len`.
     fn layout(len: usize) -> Layout {
-        Layout::new::<AtomInner<()>>()
-            .extend(
-                Layout::array::<u8>(len)
-                    .unwrap()
-            )
-            .unwrap()
-            .0
-            .pad_to_align()
+        Self::try_layout(len).unwrap()
+    }
+
+    /// Gets the layout for [AtomInner<str>] with `len`, or [None] if the
+    /// size overflows.
+    fn try_layout(len: usize) -> Option<Layout> {
+        // The string bytes start at the offset of `value` rather than at
+        // the (padded) size of the header.
+        let value_offset = std::mem::offset_of!(AtomInner<()>, value);
+        let size = value_offset.checked_add(len)?;
+        Layout::from_size_align(size, std::mem::align_of::<AtomInner<()>>())
+            .ok()
+            .map(|layout| layout.pad_to_align())
+    }
+
+    /// Returns the key, without [EXTERNAL_LEN_BIT].
+    #[inline]
+    fn key(&self) -> AtomKey {
+        AtomKey {
+            hash: self.key.hash,
+            len: self.key.len & !EXTERNAL_LEN_BIT,
+        }
+    }
+
+    /// Returns the [ExternalBytes] of the [AtomInner] at `ptr`, or [None]
+    /// if its bytes are inline.
+    /// 
+    /// # Safety
+    /// `ptr` must point to an initialized [AtomInner].
+    #[inline]
+    unsafe fn external(ptr: NonNull<AtomInner<()>>) -> Option<ExternalBytes> {
+        unsafe {
+            if ptr.as_ref().key.len & EXTERNAL_LEN_BIT == 0 {
+                return None;
+            }
+            let ptr = ptr.cast::<AtomInner<ExternalBytes>>();
+            Some(std::ptr::addr_of!((*ptr.as_ptr()).value).read())
+        }
+    }
+
+    /// Returns a pointer to the string bytes of the [AtomInner] at `ptr`.
+    /// 
+    /// # Safety
+    /// `ptr` must point to an initialized [AtomInner].
+    #[inline]
+    unsafe fn bytes_ptr(ptr: NonNull<AtomInner<()>>) -> *const u8 {
+        unsafe {
+            match Self::external(ptr) {
+                Some(external) => external.ptr.as_ptr(),
+                None => ptr.as_ptr().cast::<u8>().add(std::mem::offset_of!(AtomInner<()>, value)),
+            }
+        }
     }
     
     /// Allocates memory for an [AtomInner] with the given `len`.
     fn alloc(len: usize) -> Option<NonNull<AtomInner<()>>> {
-        let layout = Self::layout(len);
+        Self::alloc_layout(Self::try_layout(len)?)
+    }
+
+    fn alloc_layout(layout: Layout) -> Option<NonNull<AtomInner<()>>> {
+        #[cfg(feature = "count-allocs")]
+        ALLOC_COUNT.fetch_add(1, Ordering::Relaxed);
         unsafe {
             let ptr = alloc(layout);
             NonNull::new(ptr as *mut AtomInner<()>)
@@ -144,542 +589,3364 @@ impl AtomInner<()> {
     /// Allocates memory for an [AtomInner] with the given `string` and
     /// `key`, then initializes the memory with the given values.
     fn alloc_new(string: &str, key: AtomKey) -> Option<NonNull<AtomInner<()>>> {
+        Self::alloc_new_bytes(string.as_bytes(), key)
+    }
+
+    /// Allocates memory for an [AtomInner] with the given `bytes` and
+    /// `key`, then initializes the memory with the given values. The bytes
+    /// need not be UTF-8, see [AtomBytes].
+    fn alloc_new_bytes(string: &[u8], key: AtomKey) -> Option<NonNull<AtomInner<()>>> {
         let ptr = Self::alloc(string.len())?;
+        let fat_ptr = Self::fatten(ptr, string.len());
         unsafe {
             ptr.write(AtomInner {
                 key,
                 value: (),
             });
-        }
-        let mut fat_ptr = Self::fatten(ptr, string.len());
-        unsafe {
-            std::ptr::copy_nonoverlapping(string.as_ptr() as *mut u8, fat_ptr.as_mut().value.as_mut_ptr() as *mut u8, string.len());
+            let value_ptr = std::ptr::addr_of_mut!((*fat_ptr.as_ptr()).value) as *mut u8;
+            std::ptr::copy_nonoverlapping(string.as_ptr(), value_ptr, string.len());
         }
         Some(ptr)
     }
-}
-
-unsafe impl Send for AtomInner<()>
-where str: Send {}
-unsafe impl Sync for AtomInner<()>
-where str: Sync {}
-
-/// An [Atom] is a singleton reference to a `'static` lifetime string.
-/// The string lives until the end of the program, and its memory is
-/// essentially considered "leaked" during execution.
-/// 
-/// There is no way to deallocate an [Atom] safely since they are cheaply
-/// copyable with no reference counting whatsoever. That means that you
-/// should be conscientious about how many [Atom] instances you create
-/// during the lifetime of your program. Atoms are not meant to be used
-/// in place of [String].
-#[derive(Clone, Copy)]
-pub struct Atom {
-    inner: NonNull<AtomInner<()>>,
-}
-
-unsafe impl Send for Atom
-where AtomInner<()>: Send {}
-unsafe impl Sync for Atom
-where AtomInner<()>: Sync {}
 
-impl Atom {
-    #[must_use]
-    #[inline]
-    fn new_internal(string: &str, key: AtomKey) -> Self {
-        let inner = AtomInner::alloc_new(string, key).expect("Out of memory or something.");
-        Self {
-            inner,
-        }
-    }
-    
-    /// Create a new interned [Atom] string.
-    /// Ensures only one instance in memory.
-    #[must_use]
-    pub fn new(string: &str) -> Self {
-        let key = AtomKey::from_str(string);
-        let mut set_lock = INTERN_SET.lock().unwrap();
-        let atoms = set_lock.entry(key).or_insert_with(|| Vec::new());
-        for atom in atoms.iter().cloned() {
-            let atom_str = atom.as_str();
-            if atom_str == string {
-                return atom;
+    /// Allocates an [AtomInner] with `len` string bytes and encodes `chars`
+    /// directly into them. The key is computed from the encoded string.
+    /// 
+    /// # Panics
+    /// Panics if `chars` doesn't encode to exactly `len` bytes.
+    fn alloc_chars(len: usize, chars: impl IntoIterator<Item = char>) -> Option<NonNull<AtomInner<()>>> {
+        let ptr = Self::alloc(len)?;
+        let fat_ptr = Self::fatten(ptr, len);
+        let mut written = 0;
+        unsafe {
+            let value_ptr = NonNull::new_unchecked(std::ptr::addr_of_mut!((*fat_ptr.as_ptr()).value) as *mut u8);
+            for c in chars {
+                let mut buf = [0u8; 4];
+                let encoded = c.encode_utf8(&mut buf);
+                if encoded.len() > len - written {
+                    written = usize::MAX;
+                    break;
+                }
+                std::ptr::copy_nonoverlapping(encoded.as_ptr(), value_ptr.as_ptr().add(written), encoded.len());
+                written += encoded.len();
+            }
+            if written != len {
+                dealloc(ptr.as_ptr() as *mut u8, Self::layout(len));
+                panic!("chars did not encode to exactly {len} bytes");
             }
+            let string = std::str::from_utf8_unchecked(std::slice::from_raw_parts(value_ptr.as_ptr(), len));
+            ptr.write(AtomInner {
+                key: AtomKey::from_str(string),
+                value: (),
+            });
         }
-        let atom = Atom::new_internal(string, key);
-        atoms.push(atom);
-        atom
+        Some(ptr)
     }
 
-    /// Returns the [Atom]'s [AtomKey] hash.
-    #[must_use]
-    #[inline]
-    pub fn hash(&self) -> u64 {
+    /// Deallocates an [AtomInner] allocated by one of the `alloc`
+    /// functions, along with its leaked [String] buffer, if any. Borrowed
+    /// `'static` bytes are left alone.
+    /// 
+    /// # Safety
+    /// `ptr` must have been allocated by one of the `alloc` functions, and
+    /// neither `ptr` nor any reference to its string may be used
+    /// afterwards.
+    unsafe fn dealloc(ptr: NonNull<AtomInner<()>>) {
         unsafe {
-            self.inner.as_ref().key.hash
+            debug_assert!(!std::ptr::eq(ptr.as_ptr(), &*EMPTY_ATOM_INNER));
+            let layout = match Self::external(ptr) {
+                Some(external) => {
+                    if external.capacity != 0 {
+                        let len = ptr.as_ref().key().len;
+                        drop(String::from_raw_parts(external.ptr.as_ptr(), len, external.capacity));
+                    }
+                    Layout::new::<AtomInner<ExternalBytes>>()
+                }
+                None => Self::layout(ptr.as_ref().key.len),
+            };
+            dealloc(ptr.as_ptr() as *mut u8, layout);
         }
     }
-    
-    /// Returns the length of the string.
-    #[must_use]
-    #[inline]
-    pub fn len(&self) -> usize {
+
+    /// Allocates a header-only [AtomInner] whose bytes are `external`.
+    fn alloc_external(key: AtomKey, external: ExternalBytes) -> Option<NonNull<AtomInner<()>>> {
+        let ptr = Self::alloc_layout(Layout::new::<AtomInner<ExternalBytes>>())?;
         unsafe {
-            self.inner.as_ref().key.len
+            ptr.cast::<AtomInner<ExternalBytes>>().write(AtomInner {
+                key: AtomKey {
+                    hash: key.hash,
+                    len: key.len | EXTERNAL_LEN_BIT,
+                },
+                value: external,
+            });
         }
+        Some(ptr)
     }
 
-    #[must_use]
-    #[inline]
-    pub fn as_str(self) -> &'static str {
-        unsafe {
-            let inner_ref = self.inner.as_ref();
-            let len = inner_ref.key.len;
-            let str_ptr = std::ptr::slice_from_raw_parts(inner_ref, len) as *mut AtomInner<str>;
-            &str_ptr.as_ref().unwrap().value
+    /// Allocates a header-only [AtomInner] that takes ownership of the
+    /// buffer of `string`. The capacity is recorded so that the buffer
+    /// can be rebuilt.
+    fn alloc_leaked(string: String, key: AtomKey) -> Option<NonNull<AtomInner<()>>> {
+        let mut string = std::mem::ManuallyDrop::new(string);
+        let external = ExternalBytes {
+            ptr: NonNull::new(string.as_mut_ptr())?,
+            capacity: string.capacity(),
+        };
+        let ptr = Self::alloc_external(key, external);
+        if ptr.is_none() {
+            unsafe { std::mem::ManuallyDrop::drop(&mut string) };
         }
+        ptr
     }
 
-    #[must_use]
-    #[inline]
-    pub fn as_path(self) -> &'static Path {
-        self.as_str().as_ref()
+    /// Allocates a header-only [AtomInner] whose bytes are the `'static`
+    /// `string`.
+    fn alloc_borrowed(string: &'static str, key: AtomKey) -> Option<NonNull<AtomInner<()>>> {
+        Self::alloc_external(key, ExternalBytes {
+            // The bytes are never written through this pointer.
+            ptr: NonNull::new(string.as_ptr().cast_mut())?,
+            capacity: 0,
+        })
     }
+}
+unsafe impl Send for AtomInner<()>
+where str: Send {}
+unsafe impl Sync for AtomInner<()>
+where str: Sync {}
 
-    /// Compares the pointers of two [Atom] instances.
-    #[must_use]
-    #[inline]
-    pub fn ptr_eq(lhs: Self, rhs: Self) -> bool {
-        std::ptr::eq(lhs.inner.as_ptr(), rhs.inner.as_ptr())
-    }
+/// The error returned when allocating an [Atom] fails. Returned by
+/// [Atom::try_new].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtomAllocError {
+    len: usize,
+}
 
-    /// Creates a new [String] built from the [Atom] string.
+impl AtomAllocError {
+    /// The length in bytes of the string that could not be allocated.
     #[must_use]
     #[inline]
-    pub fn create_string(self) -> String {
-        String::from(self)
+    pub fn requested_len(&self) -> usize {
+        self.len
     }
 }
 
-impl<I> std::ops::Index<I> for Atom
-where str: std::ops::Index<I> {
-    type Output = <str as std::ops::Index<I>>::Output;
-    fn index(&self, index: I) -> &Self::Output {
-        &self.as_str()[index]
+impl std::fmt::Display for AtomAllocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to allocate an atom for a string of {} bytes", self.len)
     }
 }
 
-impl std::cmp::PartialEq<Atom> for Atom {
-    fn eq(&self, other: &Atom) -> bool {
-        // This works because Atoms with the same value
-        // will always have the same pointer.
-        Atom::ptr_eq(*self, *other)
-    }
+impl std::error::Error for AtomAllocError {}
 
-    fn ne(&self, other: &Atom) -> bool {
-        !Atom::ptr_eq(*self, *other)
-    }
+/// The error returned by [Atom::try_intern_budgeted] when interning a new
+/// string would exceed the byte budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverBudget {
+    len: usize,
+    budget: usize,
 }
 
-impl std::cmp::Eq for Atom {}
-
-impl std::cmp::PartialOrd<Atom> for Atom {
-    fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
-        self.as_str().partial_cmp(other.as_str())
+impl OverBudget {
+    /// The length in bytes of the string that was refused.
+    #[must_use]
+    #[inline]
+    pub fn requested_len(&self) -> usize {
+        self.len
     }
 
-    fn ge(&self, other: &Atom) -> bool {
-        self.as_str().ge(other.as_str())
+    /// The byte budget in effect when the string was refused.
+    #[must_use]
+    #[inline]
+    pub fn budget(&self) -> usize {
+        self.budget
     }
+}
 
-    fn gt(&self, other: &Atom) -> bool {
-        self.as_str().gt(other.as_str())
+impl std::fmt::Display for OverBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "interning a string of {} bytes would exceed the byte budget of {}", self.len, self.budget)
     }
+}
 
-    fn le(&self, other: &Atom) -> bool {
-        self.as_str().le(other.as_str())
-    }
+impl std::error::Error for OverBudget {}
+
+/// The error returned by [Atom::try_slice] for an invalid byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceError {
+    /// A bound of the range is past the end of the string.
+    OutOfBounds {
+        index: usize,
+        len: usize,
+    },
+    /// The range starts after it ends.
+    StartAfterEnd {
+        start: usize,
+        end: usize,
+    },
+    /// A bound of the range falls inside a multi-byte character.
+    NotCharBoundary {
+        index: usize,
+    },
+}
 
-    fn lt(&self, other: &Atom) -> bool {
-        self.as_str().lt(other.as_str())
+impl std::fmt::Display for SliceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SliceError::OutOfBounds { index, len } => write!(f, "byte index {index} is out of bounds of a string of length {len}"),
+            SliceError::StartAfterEnd { start, end } => write!(f, "slice starts at byte {start} but ends at byte {end}"),
+            SliceError::NotCharBoundary { index } => write!(f, "byte index {index} is not a char boundary"),
+        }
     }
 }
 
-impl std::cmp::Ord for Atom {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.as_str().cmp(other.as_str())
-    }
+impl std::error::Error for SliceError {}
+
+/// A problem found in the intern set by [Atom::self_check]. Atoms are
+/// identified by their address (see [Atom::addr]), since a corrupted
+/// [Atom] may not be safe to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorruptionReport {
+    /// An atom is stored in a shard other than the one for its bucket.
+    WrongShard {
+        addr: usize,
+        shard: usize,
+        expected: usize,
+    },
+    /// An atom is stored in a bucket that doesn't match its [AtomKey].
+    WrongBucket {
+        addr: usize,
+        bucket: AtomKey,
+        key: AtomKey,
+    },
+    /// An atom's stored [AtomKey] doesn't match its string.
+    StaleKey {
+        addr: usize,
+        stored: AtomKey,
+        computed: AtomKey,
+    },
+    /// An atom's bytes aren't valid UTF-8.
+    InvalidUtf8 {
+        addr: usize,
+    },
+    /// Two atoms in the intern set have the same string.
+    Duplicate {
+        first: usize,
+        second: usize,
+    },
+    /// A shard's byte count doesn't match the lengths of its atoms.
+    ByteCount {
+        shard: usize,
+        counted: usize,
+        recorded: usize,
+    },
 }
 
-// PartialEq str
-impl std::cmp::PartialEq<str> for Atom {
-    fn eq(&self, other: &str) -> bool {
-        self.as_str().eq(other)
-    }
+impl std::fmt::Display for CorruptionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorruptionReport::WrongShard { addr, shard, expected } => write!(f, "atom {addr:#x} is in shard {shard} instead of shard {expected}"),
+            CorruptionReport::WrongBucket { addr, bucket, key } => write!(f, "atom {addr:#x} with key {key:?} is in the bucket for {bucket:?}"),
+            CorruptionReport::StaleKey { addr, stored, computed } => write!(f, "atom {addr:#x} has key {stored:?} but its string has key {computed:?}"),
+            CorruptionReport::InvalidUtf8 { addr } => write!(f, "atom {addr:#x} is not valid UTF-8"),
+            CorruptionReport::Duplicate { first, second } => write!(f, "atoms {first:#x} and {second:#x} have the same string"),
+            CorruptionReport::ByteCount { shard, counted, recorded } => write!(f, "shard {shard} holds {counted} bytes but records {recorded}"),
+        }
+    }
+}
+
+/// The memory layout of an [Atom]'s allocation. Returned by
+/// [Atom::layout_info].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtomLayoutInfo {
+    /// The address of the [Atom]'s header, as returned by [Atom::addr].
+    pub addr: usize,
+    /// The size of the header allocation. For atoms whose string bytes
+    /// aren't stored inline, this covers the header only.
+    pub size: usize,
+    /// The alignment of the header allocation.
+    pub align: usize,
+    /// The offset of the string bytes from `addr`, or [None] if the bytes
+    /// aren't stored inline.
+    pub string_offset: Option<usize>,
+    /// The length of the string in bytes.
+    pub len: usize,
+}
+
+/// Summary statistics of the byte lengths of interned strings. Returned
+/// by [Atom::length_summary].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LengthSummary {
+    /// The number of atoms summarized.
+    pub count: usize,
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// The lower median.
+    pub median: usize,
+    /// The 95th percentile (nearest-rank).
+    pub p95: usize,
+}
+
+impl LengthSummary {
+    /// Summarizes `lengths`. All fields are zero if `lengths` is empty.
+    fn from_lengths(mut lengths: Vec<usize>) -> Self {
+        if lengths.is_empty() {
+            return Self::default();
+        }
+        lengths.sort_unstable();
+        let count = lengths.len();
+        let total: usize = lengths.iter().sum();
+        Self {
+            count,
+            min: lengths[0],
+            max: lengths[count - 1],
+            mean: total as f64 / count as f64,
+            median: lengths[(count - 1) / 2],
+            p95: lengths[(count * 95).div_ceil(100) - 1],
+        }
+    }
+}
+
+/// Statistics about waiting for the intern set locks. Returned by
+/// [Atom::lock_stats].
+#[cfg(feature = "instrument")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LockStats {
+    /// The cumulative time spent waiting to acquire the lock.
+    pub total_wait: std::time::Duration,
+    /// The longest single wait to acquire the lock.
+    pub max_wait: std::time::Duration,
+    /// The number of times the lock was acquired.
+    pub acquisitions: u64,
+}
+
+/// The atoms sharing a single [AtomKey].
+#[derive(Default)]
+struct Bucket {
+    atoms: Vec<Atom>,
+    /// Set once the bucket has reached the scan budget. Further atoms with
+    /// this key are stored in [InternSet::overflow].
+    overflowed: bool,
+}
+
+/// The interned atoms, bucketed by [AtomKey].
+/// 
+/// [AtomKey] only hashes the ends of long strings, so strings that only
+/// differ in the middle share a bucket. To keep lookups bounded, a bucket
+/// holds at most [SCAN_BUDGET] atoms, after which atoms with that key are
+/// stored in `overflow`, keyed additionally by a hash of the full string.
+#[derive(Default)]
+struct InternSet {
+    buckets: HashMap<AtomKey, Bucket>,
+    overflow: HashMap<(AtomKey, u64), Vec<Atom>>,
+    /// The total length in bytes of the strings in the set.
+    bytes: usize,
+    /// The atoms added by [InternSet::insert_new], with their [INSERT_SEQ]
+    /// numbers in ascending order. Only the shards of [INTERN_SET] keep a
+    /// log.
+    log: Vec<(u64, Atom)>,
+}
+
+impl InternSet {
+    /// Finds the interned [Atom] for `string`, also returning the number
+    /// of atoms that were compared against `string`.
+    fn find_counted(&self, key: AtomKey, string: &str) -> (Option<Atom>, usize) {
+        self.find_bytes_counted(key, string.as_bytes())
+    }
+
+    /// Finds the interned [Atom] for `bytes`, comparing raw bytes so that
+    /// the set can also hold the non-UTF-8 atoms of [AtomBytes].
+    fn find_bytes_counted(&self, key: AtomKey, bytes: &[u8]) -> (Option<Atom>, usize) {
+        let Some(bucket) = self.buckets.get(&key) else {
+            return (None, 0);
+        };
+        let mut scanned = 0;
+        for atom in bucket.atoms.iter().cloned() {
+            scanned += 1;
+            if atom.raw_bytes() == bytes {
+                return (Some(atom), scanned);
+            }
+        }
+        if bucket.overflowed && let Some(atoms) = self.overflow.get(&(key, hash_bytes(bytes))) {
+            for atom in atoms.iter().cloned() {
+                scanned += 1;
+                if atom.raw_bytes() == bytes {
+                    return (Some(atom), scanned);
+                }
+            }
+        }
+        (None, scanned)
+    }
+
+    /// Finds the interned [Atom] for `string`.
+    #[inline]
+    fn find(&self, key: AtomKey, string: &str) -> Option<Atom> {
+        self.find_counted(key, string).0
+    }
+
+    /// Finds the interned [Atom] for `bytes`.
+    #[inline]
+    fn find_bytes(&self, key: AtomKey, bytes: &[u8]) -> Option<Atom> {
+        self.find_bytes_counted(key, bytes).0
+    }
+
+    /// Inserts a newly allocated `atom`. The atom must not already be
+    /// interned.
+    fn insert(&mut self, atom: Atom) {
+        self.bytes += atom.len();
+        let key = atom.key();
+        let bucket = self.buckets.entry(key).or_default();
+        if !bucket.overflowed && bucket.atoms.len() < SCAN_BUDGET.load(Ordering::Relaxed) {
+            bucket.atoms.push(atom);
+            return;
+        }
+        bucket.overflowed = true;
+        self.overflow.entry((key, hash_bytes(atom.raw_bytes()))).or_default().push(atom);
+    }
+
+    /// Inserts a newly interned `atom` into this shard of [INTERN_SET],
+    /// adding its bytes to [INTERNED_BYTES] and logging it under the next
+    /// [INSERT_SEQ] number for [Atom::since]. The shard's lock must be
+    /// held, so that the log stays in order.
+    fn insert_new(&mut self, atom: Atom) {
+        INTERNED_BYTES.fetch_add(atom.len(), Ordering::Relaxed);
+        self.insert_new_reserved(atom);
+    }
+
+    /// Like [InternSet::insert_new], for an `atom` whose bytes have already
+    /// been added to [INTERNED_BYTES] (see [Atom::try_intern_budgeted]).
+    fn insert_new_reserved(&mut self, atom: Atom) {
+        self.insert(atom);
+        let seq = INSERT_SEQ.fetch_add(1, Ordering::Relaxed);
+        self.log.push((seq, atom));
+    }
+
+    /// Returns the logged atoms with sequence numbers of at least `seq`.
+    fn logged_since(&self, seq: u64) -> &[(u64, Atom)] {
+        &self.log[self.log.partition_point(|&(logged, _)| logged < seq)..]
+    }
+
+    /// Removes every atom in `atoms` that is in the set, returning the
+    /// removed atoms.
+    fn remove_many(&mut self, atoms: &std::collections::HashSet<Atom>) -> Vec<Atom> {
+        let mut removed = Vec::new();
+        let mut retain = |atom: &Atom| {
+            if atoms.contains(atom) {
+                self.bytes -= atom.len();
+                removed.push(*atom);
+                false
+            } else {
+                true
+            }
+        };
+        self.buckets.retain(|_, bucket| {
+            bucket.atoms.retain(&mut retain);
+            !bucket.atoms.is_empty() || bucket.overflowed
+        });
+        self.overflow.retain(|_, atoms| {
+            atoms.retain(&mut retain);
+            !atoms.is_empty()
+        });
+        if !removed.is_empty() {
+            self.log.retain(|(_, atom)| !atoms.contains(atom));
+        }
+        removed
+    }
+
+    /// Checks the set, which is shard `shard` of the intern set, for
+    /// corruption, adding a [CorruptionReport] to `reports` for each
+    /// problem found.
+    fn check(&self, shard: usize, reports: &mut Vec<CorruptionReport>) {
+        let mut bytes = 0usize;
+        let mut contents: HashMap<&[u8], Atom> = HashMap::new();
+        let entries = self.buckets.iter()
+            .flat_map(|(key, bucket)| bucket.atoms.iter().map(move |atom| (*key, None, *atom)))
+            .chain(self.overflow.iter().flat_map(|((key, full_hash), atoms)| {
+                atoms.iter().map(move |atom| (*key, Some(*full_hash), *atom))
+            }));
+        for (bucket, full_hash, atom) in entries {
+            let addr = atom.addr();
+            let stored = atom.key();
+            let raw = atom.raw_bytes();
+            bytes = bytes.wrapping_add(raw.len());
+            let expected_shard = (bucket.hash % SHARD_COUNT as u64) as usize;
+            if expected_shard != shard {
+                reports.push(CorruptionReport::WrongShard { addr, shard, expected: expected_shard });
+            }
+            let computed = AtomKey::from_bytes(raw);
+            if stored != computed {
+                reports.push(CorruptionReport::StaleKey { addr, stored, computed });
+            }
+            if std::str::from_utf8(raw).is_err() {
+                reports.push(CorruptionReport::InvalidUtf8 { addr });
+            }
+            if full_hash.is_some_and(|full_hash| full_hash != hash_bytes(raw)) {
+                reports.push(CorruptionReport::WrongBucket { addr, bucket, key: stored });
+                continue;
+            }
+            if stored != bucket {
+                reports.push(CorruptionReport::WrongBucket { addr, bucket, key: stored });
+            }
+            if let Some(first) = contents.insert(raw, atom) {
+                reports.push(CorruptionReport::Duplicate { first: first.addr(), second: addr });
+            }
+        }
+        if bytes != self.bytes {
+            reports.push(CorruptionReport::ByteCount { shard, counted: bytes, recorded: self.bytes });
+        }
+    }
+
+    /// Reserves room for at least `additional` more buckets.
+    fn reserve(&mut self, additional: usize) {
+        self.buckets.reserve(additional);
+    }
+
+    /// Shrinks the bucket and overflow maps as much as possible.
+    fn shrink_to_fit(&mut self) {
+        self.buckets.shrink_to_fit();
+        self.overflow.shrink_to_fit();
+    }
+
+    /// Returns the number of atoms stored for each [AtomKey], including
+    /// atoms in the overflow index.
+    fn bucket_sizes(&self) -> HashMap<AtomKey, usize> {
+        let mut sizes: HashMap<AtomKey, usize> = self.buckets.iter()
+            .map(|(key, bucket)| (*key, bucket.atoms.len()))
+            .collect();
+        for ((key, _), atoms) in self.overflow.iter() {
+            *sizes.entry(*key).or_default() += atoms.len();
+        }
+        sizes
+    }
+
+    /// Returns an iterator over every interned [Atom].
+    fn atoms(&self) -> impl Iterator<Item = Atom> + '_ {
+        self.buckets.values()
+            .flat_map(|bucket| bucket.atoms.iter().cloned())
+            .chain(self.overflow.values().flatten().cloned())
+    }
+}
+
+/// An [Atom] is a singleton reference to a `'static` lifetime string.
+/// The string lives until the end of the program, and its memory is
+/// essentially considered "leaked" during execution.
+/// 
+/// There is no way to deallocate an [Atom] safely since they are cheaply
+/// copyable with no reference counting whatsoever. That means that you
+/// should be conscientious about how many [Atom] instances you create
+/// during the lifetime of your program. Atoms are not meant to be used
+/// in place of [String].
+#[derive(Clone, Copy)]
+pub struct Atom {
+    inner: NonNull<AtomInner<()>>,
+}
+
+unsafe impl Send for Atom
+where AtomInner<()>: Send {}
+unsafe impl Sync for Atom
+where AtomInner<()>: Sync {}
+
+impl Atom {
+    #[must_use]
+    #[inline]
+    fn new_internal(string: &str, key: AtomKey) -> Self {
+        Self::try_new_internal(string, key).expect("Out of memory or something.")
+    }
+
+    #[inline]
+    fn try_new_internal(string: &str, key: AtomKey) -> Result<Self, AtomAllocError> {
+        let inner = AtomInner::alloc_new(string, key).ok_or(AtomAllocError { len: string.len() })?;
+        Ok(Self {
+            inner,
+        })
+    }
+
+    #[must_use]
+    #[inline]
+    fn new_leaked_internal(string: String, key: AtomKey) -> Self {
+        let inner = AtomInner::alloc_leaked(string, key).expect("Out of memory or something.");
+        Self {
+            inner,
+        }
+    }
+    
+    /// Returns the empty [Atom], the same [Atom] returned by
+    /// `Atom::new("")`.
+    /// 
+    /// The empty [Atom] is static and never stored in the intern set, so
+    /// this neither locks nor allocates.
+    #[must_use]
+    #[inline]
+    pub fn empty() -> Self {
+        Self {
+            inner: NonNull::from(&*EMPTY_ATOM_INNER),
+        }
+    }
+
+    /// Create a new interned [Atom] string.
+    /// Ensures only one instance in memory.
+    /// 
+    /// Interning the empty string never locks or allocates, and always
+    /// returns the same [Atom].
+    #[must_use]
+    pub fn new(string: &str) -> Self {
+        Atom::try_new(string).expect("Out of memory or something.")
+    }
+
+    /// Create a new interned [Atom] string, returning an error instead of
+    /// panicking if the allocation fails.
+    pub fn try_new(string: &str) -> Result<Self, AtomAllocError> {
+        match string.as_bytes() {
+            [] => Ok(Atom::empty()),
+            [byte] if byte.is_ascii() => Ok(ASCII_ATOMS[*byte as usize]),
+            _ => Atom::try_new_in_set(string),
+        }
+    }
+
+    /// Create a new interned [Atom] from anything string-like.
+    /// 
+    /// This is [Atom::new] for callers holding a `T: AsRef<str>`.
+    #[must_use]
+    #[inline]
+    pub fn intern(string: impl AsRef<str>) -> Self {
+        Atom::new(string.as_ref())
+    }
+
+    /// Returns the [Atom] for `string` if it has already been interned,
+    /// without interning it otherwise.
+    /// 
+    /// The empty string and single-byte ASCII strings are always interned.
+    #[must_use]
+    pub fn get(string: &str) -> Option<Self> {
+        match string.as_bytes() {
+            [] => Some(Atom::empty()),
+            [byte] if byte.is_ascii() => Some(ASCII_ATOMS[*byte as usize]),
+            _ => {
+                let key = AtomKey::from_str(string);
+                lock_intern_set(key).find(key, string)
+            }
+        }
+    }
+
+    /// Interns `string` through the intern set, bypassing [ASCII_ATOMS].
+    fn try_new_in_set(string: &str) -> Result<Self, AtomAllocError> {
+        let key = AtomKey::from_str(string);
+        let mut set_lock = lock_intern_set(key);
+        if let Some(atom) = set_lock.find(key, string) {
+            return Ok(atom);
+        }
+        let atom = Atom::try_new_internal(string, key)?;
+        set_lock.insert_new(atom);
+        drop(set_lock);
+        record_new_atom(atom);
+        Ok(atom)
+    }
+
+    /// Create a new interned [Atom] string from an owned [String].
+    /// 
+    /// If the string is already interned, `string` is dropped and the
+    /// existing [Atom] is returned. Otherwise, if `string` has no spare
+    /// capacity, its buffer becomes the [Atom]'s storage, which saves
+    /// copying the string but still allocates a small header. A `string`
+    /// with spare capacity is copied as by [Atom::new] instead, so that the
+    /// spare capacity isn't kept alive.
+    #[must_use]
+    pub fn from_string(string: String) -> Self {
+        if string.is_empty() {
+            return Atom::empty();
+        }
+        let key = AtomKey::from_str(&string);
+        let mut set_lock = lock_intern_set(key);
+        if let Some(atom) = set_lock.find(key, &string) {
+            return atom;
+        }
+        let atom = if string.capacity() == string.len() {
+            Atom::new_leaked_internal(string, key)
+        } else {
+            Atom::new_internal(&string, key)
+        };
+        set_lock.insert_new(atom);
+        drop(set_lock);
+        record_new_atom(atom);
+        atom
+    }
+
+    /// Create a new interned [Atom] from `chars`, which must encode to
+    /// exactly `len_bytes` bytes of UTF-8.
+    /// 
+    /// The chars are encoded straight into a single allocation of
+    /// `len_bytes`, with no intermediate [String]. If the string is already
+    /// interned, the allocation is freed and the existing [Atom] is
+    /// returned.
+    /// 
+    /// # Panics
+    /// Panics if `chars` doesn't encode to exactly `len_bytes` bytes.
+    #[must_use]
+    pub fn from_chars_exact(len_bytes: usize, chars: impl IntoIterator<Item = char>) -> Self {
+        if len_bytes == 0 {
+            assert!(chars.into_iter().next().is_none(), "chars did not encode to exactly 0 bytes");
+            return Atom::empty();
+        }
+        let inner = AtomInner::alloc_chars(len_bytes, chars).expect("Out of memory or something.");
+        let candidate = Atom { inner };
+        let mut set_lock = lock_intern_set(candidate.key());
+        if let Some(atom) = set_lock.find(candidate.key(), candidate.as_str()) {
+            drop(set_lock);
+            // The candidate was never shared, so nothing else refers to it.
+            unsafe { AtomInner::dealloc(inner) };
+            return atom;
+        }
+        set_lock.insert_new(candidate);
+        drop(set_lock);
+        record_new_atom(candidate);
+        candidate
+    }
+
+    /// Reads a length-prefixed string from `reader` and interns it.
+    /// 
+    /// The length is a little-endian `u64` byte count, followed by that
+    /// many bytes of UTF-8. Truncated input is an
+    /// [std::io::ErrorKind::UnexpectedEof] error, and invalid UTF-8 is an
+    /// [std::io::ErrorKind::InvalidData] error.
+    pub fn read_len_prefixed<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut len_bytes = [0u8; 8];
+        reader.read_exact(&mut len_bytes)?;
+        let len = u64::from_le_bytes(len_bytes);
+        // Read through `take` rather than allocating `len` bytes upfront, so
+        // that a bogus length in truncated input can't exhaust memory.
+        let mut bytes = Vec::new();
+        reader.take(len).read_to_end(&mut bytes)?;
+        if (bytes.len() as u64) < len {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let string = String::from_utf8(bytes)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        Ok(Atom::from_string(string))
+    }
+
+    /// Writes `atoms` to `writer` as a front-coded table, in which each
+    /// string is stored as the length of the prefix it shares with the
+    /// previous string followed by the rest of its bytes.
+    /// 
+    /// The order of `atoms` is preserved, so tables of hierarchical names
+    /// (paths, qualified names) compress best when sorted. All lengths are
+    /// LEB128 varints, starting with the number of atoms. The table is read
+    /// back with [Atom::load_table_prefix_compressed].
+    pub fn dump_table_prefix_compressed<W: Write>(atoms: &[Atom], writer: &mut W) -> std::io::Result<()> {
+        write_varint(writer, atoms.len() as u64)?;
+        let mut previous: &[u8] = &[];
+        for atom in atoms {
+            let bytes = atom.as_bytes();
+            let shared = previous.iter()
+                .zip(bytes)
+                .take_while(|(lhs, rhs)| lhs == rhs)
+                .count();
+            let suffix = &bytes[shared..];
+            write_varint(writer, shared as u64)?;
+            write_varint(writer, suffix.len() as u64)?;
+            writer.write_all(suffix)?;
+            previous = bytes;
+        }
+        Ok(())
+    }
+
+    /// Reads a table written by [Atom::dump_table_prefix_compressed] from
+    /// `reader`, interning each string, and returns the atoms in order.
+    /// 
+    /// Truncated input is an [std::io::ErrorKind::UnexpectedEof] error.
+    /// Invalid UTF-8, malformed varints, and prefixes longer than the
+    /// previous string are [std::io::ErrorKind::InvalidData] errors.
+    pub fn load_table_prefix_compressed<R: Read>(reader: &mut R) -> std::io::Result<Vec<Self>> {
+        let count = read_varint(reader)?;
+        let mut atoms = Vec::new();
+        let mut previous = Vec::new();
+        for _ in 0..count {
+            let shared = read_varint(reader)?;
+            if shared > previous.len() as u64 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "shared prefix is longer than the previous string",
+                ));
+            }
+            previous.truncate(shared as usize);
+            let suffix_len = read_varint(reader)?;
+            // Read through `take` so that a bogus length in truncated input
+            // can't exhaust memory.
+            let read = reader.take(suffix_len).read_to_end(&mut previous)?;
+            if (read as u64) < suffix_len {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            let string = std::str::from_utf8(&previous)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            atoms.push(Atom::new(string));
+        }
+        Ok(atoms)
+    }
+
+    /// Create a new interned [Atom] from a `'static` string.
+    /// 
+    /// If the string isn't already interned, the [Atom] refers to the bytes
+    /// of `string` rather than a copy of them, so only the [Atom]'s header is
+    /// allocated. Either way the [Atom] is shared with [Atom::new], so it
+    /// can be mixed with other atoms as a map key (see the `Borrow<str>`
+    /// impl).
+    #[must_use]
+    pub fn new_static(string: &'static str) -> Self {
+        match string.as_bytes() {
+            [] => return Atom::empty(),
+            [byte] if byte.is_ascii() => return ASCII_ATOMS[*byte as usize],
+            _ => (),
+        }
+        let key = AtomKey::from_str(string);
+        let mut set_lock = lock_intern_set(key);
+        if let Some(atom) = set_lock.find(key, string) {
+            return atom;
+        }
+        let inner = AtomInner::alloc_borrowed(string, key).expect("Out of memory or something.");
+        let atom = Atom { inner };
+        set_lock.insert_new(atom);
+        drop(set_lock);
+        record_new_atom(atom);
+        atom
+    }
+
+    /// Create a new interned [Atom] of `c` repeated `n` times.
+    /// 
+    /// The chars are encoded straight into the [Atom]'s storage, as with
+    /// [Atom::from_chars_exact].
+    /// 
+    /// # Panics
+    /// Panics if the length in bytes overflows [usize].
+    #[must_use]
+    pub fn repeat_char(c: char, n: usize) -> Self {
+        let len = n.checked_mul(c.len_utf8()).expect("repeat_char length overflow");
+        Atom::from_chars_exact(len, std::iter::repeat_n(c, n))
+    }
+
+    /// Create a new interned [Atom] of `path` in canonical form, so that
+    /// equivalent paths intern to the same [Atom].
+    /// 
+    /// The normalization is purely lexical: the filesystem is never
+    /// touched, so symlinks aren't resolved. Components are split on `/`.
+    /// Empty and `.` components are removed, and `..` removes the
+    /// component before it. A `..` at the start of a relative path is
+    /// kept, and one at the root of an absolute path is dropped. A path
+    /// that normalizes to nothing becomes `.` (or `/` if absolute).
+    #[must_use]
+    pub fn new_normalized_path(path: &str) -> Self {
+        let absolute = path.starts_with('/');
+        let mut components: Vec<&str> = Vec::new();
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => match components.last() {
+                    Some(&last) if last != ".." => {
+                        components.pop();
+                    }
+                    _ if absolute => {}
+                    _ => components.push(".."),
+                },
+                component => components.push(component),
+            }
+        }
+        let joined = components.join("/");
+        match (absolute, joined.is_empty()) {
+            (true, _) => Atom::new(&format!("/{joined}")),
+            (false, true) => Atom::new("."),
+            (false, false) => Atom::new(&joined),
+        }
+    }
+
+    /// Create a new interned [Atom] string along with an interned [Atom] for
+    /// each of its lines (as split by [Atom::split_lines]).
+    #[must_use]
+    pub fn new_with_lines(string: &str) -> (Self, Vec<Atom>) {
+        let lines = string.lines().map(Atom::new).collect();
+        (Atom::new(string), lines)
+    }
+
+    /// Joins `parts` with the platform's path list separator (`;` on
+    /// Windows, `:` elsewhere) and interns the result, e.g. for building a
+    /// `PATH` environment variable.
+    /// 
+    /// Unlike [std::env::join_paths], parts containing the separator are
+    /// not rejected.
+    #[must_use]
+    pub fn join_paths(parts: &[Atom]) -> Self {
+        const SEPARATOR: &str = if cfg!(windows) { ";" } else { ":" };
+        Atom::from_string(parts.iter().map(|part| part.as_str()).collect::<Vec<_>>().join(SEPARATOR))
+    }
+
+    /// Create a new interned [Atom] of `base` followed by `suffix`, e.g.
+    /// for building hierarchical names from a shared parent.
+    /// 
+    /// The result is an ordinary [Atom] that doesn't share `base`'s bytes.
+    /// `suffix` can be recovered with [Atom::strip_prefix].
+    /// 
+    /// # Panics
+    /// Panics if the combined length in bytes overflows [usize].
+    #[must_use]
+    pub fn new_suffixed(base: Atom, suffix: &str) -> Self {
+        if suffix.is_empty() {
+            return base;
+        }
+        let len = base.len().checked_add(suffix.len()).expect("new_suffixed length overflow");
+        let mut string = String::with_capacity(len);
+        string.push_str(base.as_str());
+        string.push_str(suffix);
+        Atom::from_string(string)
+    }
+
+    /// Create a new interned [Atom] from the lowercase form of `string`.
+    /// 
+    /// Both the lookup and the stored string use the lowercased form, so
+    /// strings that differ only in case intern to the same [Atom].
+    #[must_use]
+    pub fn new_lowercased(string: &str) -> Self {
+        if string.chars().any(|c| !c.to_lowercase().eq([c])) {
+            Atom::from_string(string.to_lowercase())
+        } else {
+            Atom::new(string)
+        }
+    }
+
+    /// Create a new interned [Atom] string using a custom `key` and
+    /// equality check, so that strings the caller considers equal share one
+    /// [Atom].
+    /// 
+    /// The atoms stored under `key` are compared with `eq(existing, string)`
+    /// instead of `==`. If none match, `string` is interned under `key`, so
+    /// the first string interned for a set of equal strings is the one that
+    /// is stored. `key` must be the same for all strings that `eq`
+    /// considers equal.
+    /// 
+    /// Like [Atom::new_in_namespace], these atoms are kept in a set of
+    /// their own, so [Atom::new] doesn't find them and may intern a
+    /// separate, unequal [Atom] for the same string (see the `Borrow<str>`
+    /// impl). They don't show up in [Atom::since].
+    /// 
+    /// `eq` is called without holding any lock, so it may intern strings
+    /// itself.
+    #[must_use]
+    pub fn new_with(string: &str, key: AtomKey, eq: impl Fn(&str, &str) -> bool) -> Self {
+        Atom::new_in_custom_set(&CUSTOM_KEY_SET, string, key, eq)
+    }
+
+    /// Interns `string` into `set` under `key`, for [Atom::new_with] and
+    /// [Atom::new_ascii_ci].
+    /// 
+    /// The candidates are copied out of the set and compared with `eq`
+    /// after the lock is released. If another thread added an atom under
+    /// `key` in the meantime, the new atoms are compared in turn before
+    /// `string` is inserted.
+    fn new_in_custom_set(
+        set: &Mutex<HashMap<AtomKey, Vec<Atom>>>,
+        string: &str,
+        key: AtomKey,
+        eq: impl Fn(&str, &str) -> bool,
+    ) -> Self {
+        let lock_set = || set.lock().unwrap_or_else(|err| err.into_inner());
+        let mut checked = 0;
+        loop {
+            let candidates = lock_set().get(&key).map(|atoms| atoms[checked..].to_vec()).unwrap_or_default();
+            if let Some(&atom) = candidates.iter().find(|atom| eq(atom.as_str(), string)) {
+                return atom;
+            }
+            checked += candidates.len();
+            let mut set_lock = lock_set();
+            let atoms = set_lock.entry(key).or_default();
+            if atoms.len() == checked {
+                let atom = Atom::new_internal(string, AtomKey::from_str(string));
+                atoms.push(atom);
+                drop(set_lock);
+                record_new_atom(atom);
+                return atom;
+            }
+        }
+    }
+
+    /// Create a new interned [Atom] string that ignores ASCII case, so that
+    /// strings differing only in ASCII case, such as `"Content-Type"` and
+    /// `"content-type"`, share one [Atom].
+    /// 
+    /// The first string interned for a set of such strings is the one that
+    /// is stored, so its casing is kept for display. Atoms from this
+    /// function are kept in a set of their own, apart from those of
+    /// [Atom::new] and [Atom::new_with], so `Atom::new_ascii_ci(s)` is
+    /// never [Atom::ptr_eq] to `Atom::new(s)`.
+    #[must_use]
+    pub fn new_ascii_ci(string: &str) -> Self {
+        let folded = if string.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            Cow::Owned(string.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(string)
+        };
+        let key = AtomKey::from_str(&folded);
+        Atom::new_in_custom_set(&ASCII_CI_SET, string, key, str::eq_ignore_ascii_case)
+    }
+
+    /// Create a new [Atom] that is *not* interned.
+    /// 
+    /// The [Atom] is allocated like any other, but it is never inserted
+    /// into the intern set, so it is guaranteed to be pointer-distinct from
+    /// every other [Atom], even one with the same string. Such atoms don't
+    /// dedupe: [Atom::ptr_eq] (and `==`) with an interned [Atom] of equal
+    /// content is `false`. This is useful for sentinel values.
+    /// 
+    /// Because such an [Atom] isn't equal to the interned [Atom] of the same
+    /// string, both can be keys of one `HashMap<Atom, _>`, and a lookup by
+    /// `&str` finds either one (see the `Borrow<str>` impl).
+    #[must_use]
+    pub fn new_unique(string: &str) -> Self {
+        let key = AtomKey::from_str(string);
+        Atom::new_internal(string, key)
+    }
+
+    /// Create a new [Atom] string interned within the namespace `ns`.
+    /// 
+    /// Each namespace has its own intern set, separate from the global one
+    /// used by [Atom::new]. The string of the returned [Atom] is identical,
+    /// but identity is namespace-scoped: [Atom::ptr_eq] (and `==`) is only
+    /// `true` for atoms interned with the same string in the same
+    /// namespace.
+    /// 
+    /// Atoms with the same string from different namespaces can therefore
+    /// be separate keys of one `HashMap<Atom, _>`, and a lookup by `&str`
+    /// finds either one (see the `Borrow<str>` impl).
+    /// 
+    /// Namespaced atoms aren't in the global set, so they never show up in
+    /// [Atom::since].
+    #[must_use]
+    pub fn new_in_namespace(ns: u16, string: &str) -> Self {
+        let key = AtomKey::from_str(string);
+        let mut sets_lock = NAMESPACE_SETS.lock().unwrap_or_else(|err| err.into_inner());
+        let set = sets_lock.entry(ns).or_default();
+        if let Some(atom) = set.find(key, string) {
+            return atom;
+        }
+        let atom = Atom::new_internal(string, key);
+        set.insert(atom);
+        drop(sets_lock);
+        record_new_atom(atom);
+        atom
+    }
+
+    /// Sets the number of atoms that may share a bucket before atoms with
+    /// the same [AtomKey] fall back to a full-string hash index.
+    /// 
+    /// Strings that share their first and last bytes (see
+    /// [AtomKey::from_str]) share a bucket that is scanned linearly, so
+    /// this bounds the number of string comparisons per lookup. The default
+    /// is `8`. Buckets that have already overflowed are unaffected.
+    pub fn set_scan_budget(budget: usize) {
+        SCAN_BUDGET.store(budget, Ordering::Relaxed);
+    }
+
+    /// Sets the soft limit on [Atom::total_interned_bytes] enforced by
+    /// [Atom::try_intern_budgeted]. The default is [usize::MAX], meaning no
+    /// limit. Other ways of interning ignore the limit.
+    pub fn set_byte_budget(budget: usize) {
+        BYTE_BUDGET.store(budget, Ordering::Relaxed);
+    }
+
+    /// Create a new interned [Atom] string, unless doing so would take
+    /// [Atom::total_interned_bytes] over the limit set by
+    /// [Atom::set_byte_budget].
+    /// 
+    /// Strings that are already interned are always returned, so callers
+    /// can shed load for new strings while existing ones still resolve.
+    pub fn try_intern_budgeted(string: &str) -> Result<Self, OverBudget> {
+        match string.as_bytes() {
+            [] => return Ok(Atom::empty()),
+            [byte] if byte.is_ascii() => return Ok(ASCII_ATOMS[*byte as usize]),
+            _ => (),
+        }
+        let key = AtomKey::from_str(string);
+        let mut set_lock = lock_intern_set(key);
+        if let Some(atom) = set_lock.find(key, string) {
+            return Ok(atom);
+        }
+        // The bytes are reserved before allocating, so racing threads can't
+        // take the total over the budget together.
+        let budget = BYTE_BUDGET.load(Ordering::Relaxed);
+        let reserved = INTERNED_BYTES.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+            total.checked_add(string.len()).filter(|&total| total <= budget)
+        });
+        if reserved.is_err() {
+            return Err(OverBudget { len: string.len(), budget });
+        }
+        let atom = Atom::new_internal(string, key);
+        set_lock.insert_new_reserved(atom);
+        drop(set_lock);
+        record_new_atom(atom);
+        Ok(atom)
+    }
+
+    /// Create a new interned [Atom] string from `bytes` if they are valid
+    /// UTF-8. The validated bytes are interned directly, without an
+    /// intermediate [String].
+    #[inline]
+    pub fn from_utf8(bytes: &[u8]) -> Result<Self, std::str::Utf8Error> {
+        std::str::from_utf8(bytes).map(Atom::new)
+    }
+
+    /// Create a new interned [Atom] string from `bytes`, replacing invalid
+    /// UTF-8 sequences with `U+FFFD`, like [String::from_utf8_lossy]. Valid
+    /// bytes are interned directly, without an intermediate [String].
+    #[must_use]
+    #[inline]
+    pub fn from_utf8_lossy(bytes: &[u8]) -> Self {
+        Atom::new(&String::from_utf8_lossy(bytes))
+    }
+
+    /// Create a new interned [Atom] string from `bytes` without checking
+    /// that they are valid UTF-8.
+    /// 
+    /// # Safety
+    /// `bytes` must be valid UTF-8. See [std::str::from_utf8_unchecked].
+    #[must_use]
+    #[inline]
+    pub unsafe fn from_utf8_unchecked(bytes: &[u8]) -> Self {
+        unsafe {
+            Atom::new(std::str::from_utf8_unchecked(bytes))
+        }
+    }
+
+    /// Returns every interned [Atom] whose string is exactly `len` bytes
+    /// long.
+    #[must_use]
+    pub fn atoms_with_len(len: usize) -> Vec<Atom> {
+        snapshot_intern_set().into_iter()
+            .filter(|atom| atom.len() == len)
+            .collect()
+    }
+
+    /// Runs `f`, returning its result along with every [Atom] that was newly
+    /// interned by the current thread while it ran.
+    /// 
+    /// Scopes may be nested, in which case each scope only captures the
+    /// atoms created directly within it (and not within an inner scope).
+    pub fn record_new_in<T>(f: impl FnOnce() -> T) -> (T, Vec<Atom>) {
+        let guard = RecorderGuard::push();
+        let result = f();
+        (result, guard.finish())
+    }
+
+    /// Returns every interned [Atom] whose string satisfies `pred`.
+    /// 
+    /// The intern set is only locked while taking a snapshot, so `pred` is
+    /// free to intern new atoms.
+    #[must_use]
+    pub fn filter_interned(pred: impl Fn(&str) -> bool) -> Vec<Atom> {
+        snapshot_intern_set().into_iter()
+            .filter(|atom| pred(atom.as_str()))
+            .collect()
+    }
+
+    /// Returns statistics about waiting for the intern set locks since the
+    /// start of the program.
+    #[cfg(feature = "instrument")]
+    #[must_use]
+    pub fn lock_stats() -> LockStats {
+        LockStats {
+            total_wait: std::time::Duration::from_nanos(LOCK_WAIT_TOTAL_NANOS.load(Ordering::Relaxed)),
+            max_wait: std::time::Duration::from_nanos(LOCK_WAIT_MAX_NANOS.load(Ordering::Relaxed)),
+            acquisitions: LOCK_ACQUISITIONS.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the crate's statistics counters to zero: those of
+    /// `Atom::lock_stats` with the `instrument` feature, and
+    /// `Atom::alloc_count` with the `count-allocs` feature. The interned
+    /// atoms are not affected.
+    /// 
+    /// Note: the crate doesn't count interning calls, hits or misses, so
+    /// there are no such counters to reset. Without either feature, this
+    /// does nothing.
+    pub fn reset_stats() {
+        #[cfg(feature = "instrument")]
+        {
+            LOCK_WAIT_TOTAL_NANOS.store(0, Ordering::Relaxed);
+            LOCK_WAIT_MAX_NANOS.store(0, Ordering::Relaxed);
+            LOCK_ACQUISITIONS.store(0, Ordering::Relaxed);
+        }
+        #[cfg(feature = "count-allocs")]
+        ALLOC_COUNT.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns summary statistics of the byte lengths of all interned
+    /// atoms.
+    #[must_use]
+    pub fn length_summary() -> LengthSummary {
+        let lengths: Vec<usize> = snapshot_intern_set().into_iter()
+            .map(|atom| atom.len())
+            .collect();
+        LengthSummary::from_lengths(lengths)
+    }
+
+    /// Returns the number of atom allocations made by all threads since
+    /// the start of the program or the last [Atom::reset_stats].
+    /// 
+    /// The count is global, so tests that assert on it should run where
+    /// no other thread interns at the same time, such as in a test binary
+    /// of their own.
+    #[cfg(feature = "count-allocs")]
+    #[must_use]
+    #[inline]
+    pub fn alloc_count() -> usize {
+        ALLOC_COUNT.load(Ordering::Relaxed)
+    }
+
+    /// Returns the interned [Atom] with the greatest byte length. Ties are
+    /// broken by picking the lexicographically smallest string.
+    #[must_use]
+    pub fn longest() -> Option<Atom> {
+        Atom::longest_of(snapshot_intern_set().into_iter())
+    }
+
+    /// Returns the interned [Atom] with the least byte length. Ties are
+    /// broken by picking the lexicographically smallest string.
+    /// 
+    /// The empty [Atom] is not stored in the intern set, so it is never
+    /// returned.
+    #[must_use]
+    pub fn shortest() -> Option<Atom> {
+        Atom::shortest_of(snapshot_intern_set().into_iter())
+    }
+
+    fn longest_of(atoms: impl Iterator<Item = Atom>) -> Option<Atom> {
+        atoms.max_by(|lhs, rhs| lhs.len().cmp(&rhs.len()).then_with(|| rhs.cmp(lhs)))
+    }
+
+    fn shortest_of(atoms: impl Iterator<Item = Atom>) -> Option<Atom> {
+        atoms.min_by(|lhs, rhs| lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs)))
+    }
+
+    /// Reserves room in the intern set for at least `additional` more
+    /// strings, spread evenly across its shards, so that interning a large
+    /// batch doesn't rehash the set repeatedly while holding its locks.
+    /// 
+    /// This only sizes the set's hash maps; each [Atom] is still allocated
+    /// when its string is interned.
+    pub fn reserve(additional: usize) {
+        let per_shard = additional.div_ceil(SHARD_COUNT);
+        for index in 0..SHARD_COUNT {
+            lock_shard(index).reserve(per_shard);
+        }
+    }
+
+    /// Releases the intern set's spare capacity, such as after a bulk load
+    /// or [Atom::deallocate_many].
+    /// 
+    /// This only shrinks the set's hash maps; it doesn't free any [Atom].
+    pub fn shrink_to_fit() {
+        for index in 0..SHARD_COUNT {
+            lock_shard(index).shrink_to_fit();
+        }
+    }
+
+    /// Returns each [AtomKey] in the intern set along with the number of
+    /// atoms stored under it, taken from a snapshot of the set.
+    pub fn bucket_sizes() -> impl Iterator<Item = (AtomKey, usize)> {
+        (0..SHARD_COUNT).flat_map(|index| lock_shard(index).bucket_sizes())
+    }
+
+    /// Removes `atoms` from the intern set and frees them, taking each
+    /// shard's lock once for the whole batch.
+    /// 
+    /// Atoms that aren't in the intern set (such as the empty atom or atoms
+    /// from [Atom::new_unique], [Atom::new_in_namespace] or
+    /// [Atom::new_with]) and single-byte ASCII atoms, which are shared by a
+    /// static table, are left alone.
+    /// The buffers taken over by [Atom::from_string] are freed along with
+    /// their atoms, but the `'static` strings of [Atom::new_static] are
+    /// not. Interning a freed string again allocates a fresh [Atom].
+    /// 
+    /// # Safety
+    /// No copy of any of the given atoms, nor any string borrowed from one
+    /// (including `'static` ones from [Atom::as_str]), may be used after
+    /// this call. This includes atoms cached by [atom!], atoms registered
+    /// with [Atom::id], and copies held by other threads.
+    pub unsafe fn deallocate_many(atoms: &[Atom]) {
+        let atoms: std::collections::HashSet<Atom> = atoms.iter()
+            .copied()
+            .filter(|atom| !matches!(atom.as_bytes(), [byte] if byte.is_ascii()))
+            .collect();
+        for index in 0..SHARD_COUNT {
+            let removed = lock_shard(index).remove_many(&atoms);
+            INTERNED_BYTES.fetch_sub(removed.iter().map(Atom::len).sum(), Ordering::Relaxed);
+            atom_id::forget(&removed);
+            for atom in removed {
+                unsafe { AtomInner::dealloc(atom.inner) };
+            }
+        }
+    }
+
+    /// Removes every [Atom] from the intern set and frees it, so that
+    /// memory use can be measured from a clean slate.
+    /// 
+    /// The single-byte ASCII atoms, which are shared by a static table, and
+    /// the empty atom are kept. Namespaced atoms (see
+    /// [Atom::new_in_namespace]) and atoms from [Atom::new_unique] aren't
+    /// in the intern set and are unaffected. Interning a string again after
+    /// the reset allocates a fresh [Atom].
+    /// 
+    /// # Safety
+    /// This has the same contract as [Atom::deallocate_many] for every
+    /// [Atom] in the intern set: no copy of one, nor any string borrowed
+    /// from one, may be used after this call. This includes atoms cached by
+    /// [atom!], atoms held by other tests running in parallel, and the
+    /// atoms returned by [AtomId::resolve]. Strings interned by other
+    /// threads during the reset may or may not be freed.
+    #[cfg(feature = "reset")]
+    pub unsafe fn reset_intern_set() {
+        unsafe { Atom::deallocate_many(&snapshot_intern_set()) };
+    }
+
+    /// Walks the intern set looking for corruption, such as atoms stored
+    /// under the wrong key or shard, atoms whose key doesn't match their
+    /// string, and atoms with the same string, which would indicate a
+    /// deduplication bug. Meant for catching regressions in tests and
+    /// fuzzing.
+    /// 
+    /// Each shard is locked in turn, so atoms interned by other threads
+    /// during the check may or may not be checked.
+    pub fn self_check() -> Result<(), Vec<CorruptionReport>> {
+        let mut reports = Vec::new();
+        for index in 0..SHARD_COUNT {
+            lock_shard(index).check(index, &mut reports);
+        }
+        if reports.is_empty() {
+            Ok(())
+        } else {
+            Err(reports)
+        }
+    }
+
+    /// Returns a snapshot of every [Atom] in the intern set, in no
+    /// particular order.
+    /// 
+    /// The intern set is only locked while taking the snapshot.
+    pub fn iter_all() -> impl Iterator<Item = Atom> {
+        snapshot_intern_set().into_iter()
+    }
+
+    /// Returns the number of distinct strings in the intern set.
+    #[must_use]
+    pub fn intern_count() -> usize {
+        (0..SHARD_COUNT).map(|index| lock_shard(index).atoms().count()).sum()
+    }
+
+    /// Returns the total length in bytes of the strings in the intern set,
+    /// not counting the headers of their atoms.
+    #[must_use]
+    pub fn total_interned_bytes() -> usize {
+        INTERNED_BYTES.load(Ordering::Relaxed)
+    }
+
+    /// Returns the [Atom]'s [AtomKey] hash.
+    #[must_use]
+    #[inline]
+    pub fn hash(&self) -> u64 {
+        unsafe {
+            self.inner.as_ref().key().hash
+        }
+    }
+    
+    /// Returns the [Atom]'s [AtomKey].
+    #[must_use]
+    #[inline]
+    pub fn key(&self) -> AtomKey {
+        unsafe {
+            self.inner.as_ref().key()
+        }
+    }
+
+    /// Compares only the stored [AtomKey]s of two atoms, without accessing
+    /// either string.
+    /// 
+    /// If the keys differ, the strings are guaranteed to differ. Equal keys
+    /// do *not* imply equal strings, since the key only hashes the head and
+    /// tail of long strings.
+    #[must_use]
+    #[inline]
+    pub fn key_eq(self, other: Atom) -> bool {
+        self.key() == other.key()
+    }
+
+    /// Returns the length of the string.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        unsafe {
+            self.inner.as_ref().key().len
+        }
+    }
+
+    /// Returns `true` if the string is empty.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        unsafe {
+            std::str::from_utf8_unchecked(self.raw_bytes())
+        }
+    }
+
+    /// Returns the bytes of the [Atom], which are only guaranteed to be
+    /// UTF-8 for atoms that aren't the storage of an [AtomBytes].
+    #[must_use]
+    #[inline]
+    fn raw_bytes(self) -> &'static [u8] {
+        unsafe {
+            std::slice::from_raw_parts(AtomInner::bytes_ptr(self.inner), self.len())
+        }
+    }
+
+    /// Borrows the string for the lifetime of `self` rather than `'static`,
+    /// for generic code where [Atom::as_str]'s `'static` lifetime gets in
+    /// the way of inference.
+    #[allow(clippy::should_implement_trait)]
+    #[must_use]
+    #[inline]
+    pub fn borrow(&self) -> &str {
+        self.as_str()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn as_path(self) -> &'static Path {
+        self.as_str().as_ref()
+    }
+
+    /// Boxes the [Atom] as a [std::fmt::Display] trait object. The box holds
+    /// the pointer-sized [Atom] itself rather than a copy of its string.
+    #[must_use]
+    #[inline]
+    pub fn into_display_box(self) -> Box<dyn std::fmt::Display + Send + Sync> {
+        Box::new(self)
+    }
+
+    /// Compares the pointers of two [Atom] instances.
+    #[must_use]
+    #[inline]
+    pub fn ptr_eq(lhs: Self, rhs: Self) -> bool {
+        std::ptr::eq(lhs.inner.as_ptr(), rhs.inner.as_ptr())
+    }
+
+    /// Returns the address of the [Atom]'s allocation.
+    /// 
+    /// Two atoms have the same address if and only if they are
+    /// [Atom::ptr_eq], which makes this usable as an identity key for side
+    /// tables. Addresses are not stable across runs of the program.
+    #[must_use]
+    #[inline]
+    pub fn addr(self) -> usize {
+        self.inner.as_ptr() as usize
+    }
+
+    /// Returns the memory layout of the [Atom]'s allocation, for debugging.
+    #[must_use]
+    pub fn layout_info(self) -> AtomLayoutInfo {
+        let external = unsafe { AtomInner::external(self.inner) };
+        let layout = match external {
+            Some(_) => Layout::new::<AtomInner<ExternalBytes>>(),
+            None => AtomInner::layout(self.len()),
+        };
+        // The empty atom is static rather than allocated.
+        let inline = external.is_none() && !self.is_empty();
+        AtomLayoutInfo {
+            addr: self.addr(),
+            size: layout.size(),
+            align: layout.align(),
+            string_offset: inline.then(|| self.as_ptr() as usize - self.addr()),
+            len: self.len(),
+        }
+    }
+
+    /// Creates a new [String] built from the [Atom] string.
+    #[must_use]
+    #[inline]
+    pub fn create_string(self) -> String {
+        String::from(self)
+    }
+
+    /// Returns the interned remainder after removing `prefix`, or [None]
+    /// if the string does not start with `prefix`.
+    #[must_use]
+    pub fn strip_prefix(self, prefix: &str) -> Option<Atom> {
+        self.as_str().strip_prefix(prefix).map(Atom::new)
+    }
+
+    /// Returns the interned remainder after removing `suffix`, or [None]
+    /// if the string does not end with `suffix`.
+    #[must_use]
+    pub fn strip_suffix(self, suffix: &str) -> Option<Atom> {
+        self.as_str().strip_suffix(suffix).map(Atom::new)
+    }
+
+    /// Returns the substring covering the characters (not bytes) in
+    /// `char_range`, or [None] if the range is out of bounds.
+    #[must_use]
+    pub fn char_slice(self, char_range: Range<usize>) -> Option<&'static str> {
+        if char_range.start > char_range.end {
+            return None;
+        }
+        let string = self.as_str();
+        let mut boundaries = string.char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(string.len()));
+        let start = boundaries.nth(char_range.start)?;
+        let end = if char_range.end == char_range.start {
+            start
+        } else {
+            boundaries.nth(char_range.end - char_range.start - 1)?
+        };
+        Some(&string[start..end])
+    }
+
+    /// Interns the substring covering the bytes in `range`. A range covering
+    /// the whole string returns `self`.
+    /// 
+    /// # Panics
+    /// Panics like indexing a [str] if `range` is out of bounds or a bound
+    /// isn't on a char boundary. See [Atom::try_slice] for a fallible
+    /// version.
+    #[must_use]
+    pub fn sub_atom<R: std::ops::RangeBounds<usize>>(self, range: R) -> Atom {
+        let sub = &self.as_str()[(range.start_bound().cloned(), range.end_bound().cloned())];
+        if sub.len() == self.len() {
+            self
+        } else {
+            Atom::new(sub)
+        }
+    }
+
+    /// Returns the substring covering the bytes in `range`, or a
+    /// [SliceError] describing why the range is invalid.
+    /// 
+    /// This is the fallible counterpart of indexing the [Atom] by a range.
+    pub fn try_slice(self, range: impl std::ops::RangeBounds<usize>) -> Result<&'static str, SliceError> {
+        use std::ops::Bound;
+        let string = self.as_str();
+        let len = string.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).ok_or(SliceError::OutOfBounds { index: start, len })?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).ok_or(SliceError::OutOfBounds { index: end, len })?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        if start > end {
+            return Err(SliceError::StartAfterEnd { start, end });
+        }
+        if let Some(index) = [start, end].into_iter().find(|&index| index > len) {
+            return Err(SliceError::OutOfBounds { index, len });
+        }
+        if let Some(index) = [start, end].into_iter().find(|&index| !string.is_char_boundary(index)) {
+            return Err(SliceError::NotCharBoundary { index });
+        }
+        Ok(&string[start..end])
+    }
+
+    /// Splits the string by `sep`, interning each piece, and returns the
+    /// pieces as an exactly sized boxed slice.
+    /// 
+    /// As with [str::split], an empty string yields a single empty [Atom]
+    /// and leading or trailing separators yield empty atoms.
+    #[must_use]
+    pub fn split_to_boxed(self, sep: char) -> Box<[Atom]> {
+        self.as_str().split(sep).map(Atom::new).collect()
+    }
+
+    /// Compares two atoms with a custom string comparator, such as
+    /// [natural_cmp], instead of the lexicographic [Ord] implementation.
+    #[must_use]
+    #[inline]
+    pub fn cmp_with(self, other: Atom, cmp: impl Fn(&str, &str) -> std::cmp::Ordering) -> std::cmp::Ordering {
+        cmp(self.as_str(), other.as_str())
+    }
+
+    /// Returns a [Debug](std::fmt::Debug) wrapper that also prints the
+    /// [Atom]'s address, e.g. `Atom("foo" @ 0x55d0c2a0f2c0)`.
+    /// 
+    /// This helps verify whether two atoms are the same instance. The
+    /// [Debug](std::fmt::Debug) implementation of [Atom] itself prints only
+    /// the quoted string.
+    #[must_use]
+    #[inline]
+    pub fn debug_identity(self) -> impl std::fmt::Debug {
+        DebugIdentity(self)
+    }
+
+    /// Returns `true` if the string's bytes equal `other`.
+    /// 
+    /// The stored length is compared first, so a length mismatch is
+    /// rejected without reading the string.
+    #[must_use]
+    #[inline]
+    pub fn bytes_eq(self, other: &[u8]) -> bool {
+        self.len() == other.len() && self.as_bytes() == other
+    }
+
+    /// Returns `true` if the string starts with any of `prefixes`.
+    #[must_use]
+    pub fn starts_with_any(self, prefixes: &[&str]) -> bool {
+        prefixes.iter().any(|prefix| self.as_str().starts_with(prefix))
+    }
+
+    /// Returns `true` if the string ends with any of `suffixes`.
+    #[must_use]
+    pub fn ends_with_any(self, suffixes: &[&str]) -> bool {
+        suffixes.iter().any(|suffix| self.as_str().ends_with(suffix))
+    }
+
+    /// Returns `true` if any [Atom] in `set` is the same [Atom], comparing
+    /// pointers rather than strings.
+    #[must_use]
+    pub fn matches_any_atom(self, set: &[Atom]) -> bool {
+        set.iter().any(|&atom| Atom::ptr_eq(self, atom))
+    }
+
+    /// Returns the interned concatenation of the string and `other`, the
+    /// same as [Atom::new_suffixed]. Also available as `atom + other`.
+    /// 
+    /// The concatenation is built in a [String] that becomes the new
+    /// [Atom]'s storage (see [Atom::from_string]), so the bytes are only
+    /// copied once, and the buffer is freed if the string is already
+    /// interned.
+    #[must_use]
+    #[inline]
+    pub fn concat(self, other: &str) -> Atom {
+        Atom::new_suffixed(self, other)
+    }
+
+    /// Appends the [Atom]'s sort key to `out`, for building a batch of keys
+    /// for radix or external sorting without allocating per [Atom].
+    /// 
+    /// The key is the string's UTF-8 bytes, which compare in the same order
+    /// as [Atom]'s [Ord]. Keys aren't delimited, so callers writing several
+    /// into one buffer must record where each ends.
+    #[inline]
+    pub fn write_sort_key(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    /// Returns the interned uppercase form of the string, like
+    /// [str::to_uppercase]. If the string is already uppercase, `self` is
+    /// returned without allocating.
+    #[must_use]
+    pub fn to_uppercase_atom(self) -> Atom {
+        if self.as_str().chars().all(|c| c.to_uppercase().eq([c])) {
+            self
+        } else {
+            Atom::from_string(self.as_str().to_uppercase())
+        }
+    }
+
+    /// Returns the interned lowercase form of the string, like
+    /// [str::to_lowercase]. If the string is already lowercase, `self` is
+    /// returned without allocating.
+    #[must_use]
+    pub fn to_lowercase_atom(self) -> Atom {
+        if self.as_str().chars().all(|c| c.to_lowercase().eq([c])) {
+            self
+        } else {
+            Atom::from_string(self.as_str().to_lowercase())
+        }
+    }
+
+    /// Returns `true` if the string equals `other`, ignoring ASCII case.
+    #[must_use]
+    #[inline]
+    pub fn eq_ignore_ascii_case(self, other: &str) -> bool {
+        self.as_str().eq_ignore_ascii_case(other)
+    }
+
+    /// Splits the string into lines and interns each line.
+    /// 
+    /// Lines are split like [str::lines]: both `\n` and `\r\n` endings are
+    /// stripped, and a trailing line ending doesn't produce an empty final
+    /// line.
+    #[must_use]
+    pub fn split_lines(self) -> Vec<Atom> {
+        self.as_str().lines().map(Atom::new).collect()
+    }
+
+    /// Splits the string before the char at `char_idx`, interning both
+    /// parts. Returns [None] if `char_idx` is greater than the number of
+    /// chars.
+    #[must_use]
+    pub fn split_at_char(self, char_idx: usize) -> Option<(Atom, Atom)> {
+        let string = self.as_str();
+        let byte_idx = match string.char_indices().nth(char_idx) {
+            Some((byte_idx, _)) => byte_idx,
+            None if string.chars().count() == char_idx => string.len(),
+            None => return None,
+        };
+        let (head, tail) = string.split_at(byte_idx);
+        Some((Atom::new(head), Atom::new(tail)))
+    }
+
+    /// Splits the string by `sep` like [str::split_terminator], interning
+    /// each piece. A trailing separator doesn't produce an empty final
+    /// [Atom].
+    pub fn split_terminator(self, sep: char) -> impl DoubleEndedIterator<Item = Atom> {
+        self.as_str().split_terminator(sep).map(Atom::new)
+    }
+
+    /// Splits the string by `sep` into at most `n` pieces like
+    /// [str::splitn], interning each piece. The last piece contains the
+    /// remainder of the string.
+    pub fn splitn(self, n: usize, sep: char) -> impl Iterator<Item = Atom> {
+        self.as_str().splitn(n, sep).map(Atom::new)
+    }
+
+    /// Splits the string by `sep` into at most `n` pieces, starting from
+    /// the end, like [str::rsplitn], interning each piece. The last piece
+    /// contains the remainder of the string.
+    pub fn rsplitn(self, n: usize, sep: char) -> impl Iterator<Item = Atom> {
+        self.as_str().rsplitn(n, sep).map(Atom::new)
+    }
+
+    /// Returns the number of extended grapheme clusters in the string.
+    #[cfg(feature = "unicode-segmentation")]
+    #[must_use]
+    pub fn grapheme_count(self) -> usize {
+        unicode_segmentation::UnicodeSegmentation::graphemes(self.as_str(), true).count()
+    }
+
+    /// Returns the interned prefix containing at most `max` extended
+    /// grapheme clusters, so that truncation never splits a cluster.
+    #[cfg(feature = "unicode-segmentation")]
+    #[must_use]
+    pub fn truncate_graphemes(self, max: usize) -> Atom {
+        let string = self.as_str();
+        match unicode_segmentation::UnicodeSegmentation::grapheme_indices(string, true).nth(max) {
+            Some((end, _)) => Atom::new(&string[..end]),
+            None => self,
+        }
+    }
+
+    /// Interns every non-overlapping match of `re` in `haystack`, in order.
+    #[cfg(feature = "regex")]
+    #[must_use]
+    pub fn intern_matches(haystack: &str, re: &regex::Regex) -> Vec<Atom> {
+        re.find_iter(haystack)
+            .map(|found| Atom::new(found.as_str()))
+            .collect()
+    }
+}
+
+impl<I> std::ops::Index<I> for Atom
+where str: std::ops::Index<I> {
+    type Output = <str as std::ops::Index<I>>::Output;
+    fn index(&self, index: I) -> &Self::Output {
+        &self.as_str()[index]
+    }
+}
+
+impl std::cmp::PartialEq<Atom> for Atom {
+    fn eq(&self, other: &Atom) -> bool {
+        // This works because Atoms with the same value
+        // will always have the same pointer.
+        Atom::ptr_eq(*self, *other)
+    }
+}
+
+impl std::cmp::Eq for Atom {}
+
+impl std::cmp::PartialOrd<Atom> for Atom {
+    fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+
+    fn ge(&self, other: &Atom) -> bool {
+        self.as_str().ge(other.as_str())
+    }
+
+    fn gt(&self, other: &Atom) -> bool {
+        self.as_str().gt(other.as_str())
+    }
+
+    fn le(&self, other: &Atom) -> bool {
+        self.as_str().le(other.as_str())
+    }
+
+    fn lt(&self, other: &Atom) -> bool {
+        self.as_str().lt(other.as_str())
+    }
+}
+
+impl std::cmp::Ord for Atom {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/// Implements `PartialEq` and `PartialOrd` between [Atom] and each string
+/// type, in both directions, by comparing the string contents.
+macro_rules! impl_str_cmp {
+    ($($ty:ty),* $(,)?) => {$(
+        impl std::cmp::PartialEq<$ty> for Atom {
+            fn eq(&self, other: &$ty) -> bool {
+                self.as_str().eq(AsRef::<str>::as_ref(other))
+            }
+        }
+
+        impl std::cmp::PartialEq<Atom> for $ty {
+            fn eq(&self, other: &Atom) -> bool {
+                AsRef::<str>::as_ref(self).eq(other.as_str())
+            }
+        }
+
+        impl std::cmp::PartialOrd<$ty> for Atom {
+            fn partial_cmp(&self, other: &$ty) -> Option<std::cmp::Ordering> {
+                self.as_str().partial_cmp(AsRef::<str>::as_ref(other))
+            }
+
+            fn ge(&self, other: &$ty) -> bool {
+                self.as_str().ge(AsRef::<str>::as_ref(other))
+            }
+
+            fn gt(&self, other: &$ty) -> bool {
+                self.as_str().gt(AsRef::<str>::as_ref(other))
+            }
+
+            fn le(&self, other: &$ty) -> bool {
+                self.as_str().le(AsRef::<str>::as_ref(other))
+            }
+
+            fn lt(&self, other: &$ty) -> bool {
+                self.as_str().lt(AsRef::<str>::as_ref(other))
+            }
+        }
+
+        impl std::cmp::PartialOrd<Atom> for $ty {
+            fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
+                AsRef::<str>::as_ref(self).partial_cmp(other.as_str())
+            }
+
+            fn ge(&self, other: &Atom) -> bool {
+                AsRef::<str>::as_ref(self).ge(other.as_str())
+            }
+
+            fn gt(&self, other: &Atom) -> bool {
+                AsRef::<str>::as_ref(self).gt(other.as_str())
+            }
+
+            fn le(&self, other: &Atom) -> bool {
+                AsRef::<str>::as_ref(self).le(other.as_str())
+            }
+
+            fn lt(&self, other: &Atom) -> bool {
+                AsRef::<str>::as_ref(self).lt(other.as_str())
+            }
+        }
+    )*};
+}
+
+impl_str_cmp!(str, &str, String);
+
+impl std::ops::Deref for Atom {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Atom {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<Path> for Atom {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+/// Allows looking up `HashMap<Atom, _>` and similar collections by `&str`.
+/// 
+/// [Atom] equality compares pointers, while hashing and `&str` lookups use
+/// the string. Interned atoms have one pointer per string, so the two
+/// agree. Atoms outside the intern set, such as those from
+/// [Atom::new_unique] or [Atom::new_in_namespace] (and atoms from
+/// [Atom::new_with] that share a string with an interned one), can have
+/// the same string as another [Atom] without being equal to it. A
+/// collection holding several such keys treats them as distinct, and a
+/// `&str` lookup returns whichever of them it finds first. Atoms from
+/// [Atom::new] and [Atom::new_static] never conflict this way.
+impl std::borrow::Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::borrow::Borrow<Path> for Atom {
+    fn borrow(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl From<Atom> for String {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        value.as_str().to_owned()
+    }
+}
+
+impl From<Atom> for Cow<'static, str> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Cow::Borrowed(value.as_str())
+    }
+}
+
+impl From<Atom> for Box<str> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Box::from(value.as_str())
+    }
+}
+
+impl From<Atom> for Rc<str> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Rc::from(value.as_str())
+    }
+}
+
+impl From<Atom> for Arc<str> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Arc::from(value.as_str())
+    }
+}
+
+impl From<Atom> for Vec<u8> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Self::from(value.as_bytes())
+    }
+}
+
+impl From<Atom> for Box<[u8]> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Box::from(value.as_bytes())
+    }
+}
+
+impl From<Atom> for Arc<[u8]> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Arc::from(value.as_bytes())
+    }
+}
+
+impl From<Atom> for Vec<char> {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        Self::from_iter(value.chars())
+    }
+}
+
+impl From<Atom> for &'static str {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        value.as_str()
+    }
+}
+
+impl From<Atom> for PathBuf {
+    #[inline]
+    fn from(value: Atom) -> Self {
+        PathBuf::from(value.as_str())
+    }
+}
+
+impl From<&str> for Atom {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Atom::new(value)
+    }
+}
+
+impl From<&mut str> for Atom {
+    #[inline]
+    fn from(value: &mut str) -> Self {
+        Atom::new(value)
+    }
+}
+
+impl From<&String> for Atom {
+    #[inline]
+    fn from(value: &String) -> Self {
+        Atom::new(value)
+    }
+}
+
+impl From<String> for Atom {
+    #[inline]
+    fn from(value: String) -> Self {
+        Atom::from_string(value)
+    }
+}
+
+impl From<Box<str>> for Atom {
+    #[inline]
+    fn from(value: Box<str>) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl From<Rc<str>> for Atom {
+    #[inline]
+    fn from(value: Rc<str>) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl From<Arc<str>> for Atom {
+    #[inline]
+    fn from(value: Arc<str>) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Atom {
+    #[inline]
+    fn from(value: Cow<'a, str>) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl std::ops::Add<&str> for Atom {
+    type Output = Atom;
+    #[inline]
+    fn add(self, rhs: &str) -> Self::Output {
+        self.concat(rhs)
+    }
+}
+
+impl Default for Atom {
+    #[inline]
+    fn default() -> Self {
+        Atom::empty()
+    }
+}
+
+impl std::str::FromStr for Atom {
+    type Err = std::convert::Infallible;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Atom::new(s))
+    }
+}
+
+impl std::fmt::Display for Atom {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Debug for Atom {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Atom {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Atom {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AtomVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for AtomVisitor {
+            type Value = Atom;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Atom::new(v))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(Atom::from_string(v))
+            }
+        }
+
+        deserializer.deserialize_str(AtomVisitor)
+    }
+}
+
+/// Returned by [Atom::debug_identity].
+struct DebugIdentity(Atom);
+
+impl std::fmt::Debug for DebugIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Atom({:?} @ {:p})", self.0.as_str(), self.0.inner)
+    }
+}
+
+impl std::hash::Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash the string content rather than the key so that
+        // `hash(atom) == hash(atom.as_str())`, which `Borrow<str>`
+        // requires for `HashMap<Atom, _>` lookups by `&str`.
+        self.as_str().hash(state);
+    }
+}
+
+/// Extension trait for interning the strings yielded by an iterator.
+pub trait InternExt: Iterator + Sized
+where Self::Item: AsRef<str> {
+    /// Returns an iterator that interns each string as an [Atom].
+    #[must_use]
+    #[inline]
+    fn interned(self) -> Interned<Self> {
+        Interned {
+            iter: self,
+        }
+    }
+}
+
+impl<I> InternExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<str> {}
+
+/// An iterator that interns each string of an inner iterator. Created by
+/// [InternExt::interned].
+#[derive(Debug, Clone)]
+pub struct Interned<I> {
+    iter: I,
+}
+
+impl<I> Iterator for Interned<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str> {
+    type Item = Atom;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|string| Atom::new(string.as_ref()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for Interned<I>
+where
+    I: DoubleEndedIterator,
+    I::Item: AsRef<str> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|string| Atom::new(string.as_ref()))
+    }
+}
+
+impl<I> ExactSizeIterator for Interned<I>
+where
+    I: ExactSizeIterator,
+    I::Item: AsRef<str> {}
+
+impl<I> std::iter::FusedIterator for Interned<I>
+where
+    I: std::iter::FusedIterator,
+    I::Item: AsRef<str> {}
+
+/// Declares a newtype around [Atom] for domain-typed interned strings.
+/// 
+/// The generated type is `Copy` and gets `new(&str)`, `atom()`,
+/// `as_str()`, `Deref<Target = str>`, `AsRef<str>`, `Display`, `Debug`,
+/// the comparison traits, comparisons with `str` and `&str`, and
+/// conversions from `&str` and to and from [Atom].
+/// 
+/// ```
+/// atom_str::atom_newtype! {
+///     /// The name of a symbol.
+///     pub SymbolName
+/// }
+/// let name = SymbolName::new("main");
+/// assert_eq!(name, "main");
+/// ```
+#[macro_export]
+macro_rules! atom_newtype {
+    ($(#[$meta:meta])* $vis:vis $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name($crate::Atom);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Interns `string` and wraps the resulting [Atom]($crate::Atom).
+            #[must_use]
+            #[inline]
+            pub fn new(string: &str) -> Self {
+                Self($crate::Atom::new(string))
+            }
+
+            /// Returns the wrapped [Atom]($crate::Atom).
+            #[must_use]
+            #[inline]
+            pub fn atom(self) -> $crate::Atom {
+                self.0
+            }
+
+            #[must_use]
+            #[inline]
+            pub fn as_str(self) -> &'static str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::std::ops::Deref for $name {
+            type Target = str;
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                self.0.as_str()
+            }
+        }
+
+        impl ::std::convert::AsRef<str> for $name {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+
+        impl ::std::cmp::PartialEq<str> for $name {
+            #[inline]
+            fn eq(&self, other: &str) -> bool {
+                self.0.as_str() == other
+            }
+        }
+
+        impl ::std::cmp::PartialEq<&str> for $name {
+            #[inline]
+            fn eq(&self, other: &&str) -> bool {
+                self.0.as_str() == *other
+            }
+        }
+
+        impl ::std::convert::From<&str> for $name {
+            #[inline]
+            fn from(value: &str) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl ::std::convert::From<$crate::Atom> for $name {
+            #[inline]
+            fn from(value: $crate::Atom) -> Self {
+                Self(value)
+            }
+        }
+
+        impl ::std::convert::From<$name> for $crate::Atom {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+/// Interns a string literal once per call site.
+/// 
+/// The [Atom] is cached in a `static` local to the call site, so after the
+/// first evaluation the macro returns the cached [Atom] without locking
+/// the intern set.
+/// 
+/// ```
+/// use atom_str::{atom, Atom};
+/// let keyword = atom!("fn");
+/// assert!(Atom::ptr_eq(keyword, Atom::new("fn")));
+/// ```
+#[macro_export]
+macro_rules! atom {
+    ($string:literal) => {{
+        static ATOM: ::std::sync::OnceLock<$crate::Atom> = ::std::sync::OnceLock::new();
+        *ATOM.get_or_init(|| $crate::Atom::new($string))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Locks every shard of [INTERN_SET], in order.
+    fn lock_all_shards() -> Vec<MutexGuard<'static, InternSet>> {
+        (0..SHARD_COUNT).map(lock_shard).collect()
+    }
+    
+    #[test]
+    fn substring_test() {
+        let atom = Atom::new("0123456789");
+        assert_eq!(&atom[1..4], "123");
+    }
+
+    #[test]
+    fn from_string_hit_test() {
+        let existing = Atom::new("from_string hit");
+        let atom = Atom::from_string(String::from("from_string hit"));
+        assert!(Atom::ptr_eq(existing, atom));
+    }
 
-    fn ne(&self, other: &str) -> bool {
-        self.as_str().ne(other)
+    #[test]
+    fn from_string_miss_test() {
+        let string = String::from("from_string miss");
+        let buffer = string.as_ptr();
+        let atom = Atom::from_string(string);
+        assert_eq!(atom.as_ptr(), buffer);
+        assert!(Atom::ptr_eq(atom, Atom::new("from_string miss")));
     }
-}
 
-impl std::cmp::PartialEq<Atom> for str {
-    fn eq(&self, other: &Atom) -> bool {
-        self.eq(other.as_str())
+    #[test]
+    fn atoms_with_len_test() {
+        let short = Atom::new("#w");
+        let codes = [Atom::new("#wa"), Atom::new("#wb"), Atom::new("#wc")];
+        let long = Atom::new("#wlen");
+        let found = Atom::atoms_with_len(3);
+        assert!(codes.iter().all(|code| found.contains(code)));
+        assert!(!found.contains(&short));
+        assert!(!found.contains(&long));
+        assert!(found.iter().all(|atom| atom.len() == 3));
     }
 
-    fn ne(&self, other: &Atom) -> bool {
-        self.ne(other.as_str())
+    #[test]
+    fn strip_affix_test() {
+        let atom = Atom::new("ns::name");
+        assert!(Atom::ptr_eq(atom.strip_prefix("ns::").unwrap(), Atom::new("name")));
+        assert!(Atom::ptr_eq(atom.strip_suffix("::name").unwrap(), Atom::new("ns")));
+        assert_eq!(atom.strip_prefix("other::"), None);
+        assert_eq!(atom.strip_suffix("::other"), None);
+        assert!(Atom::ptr_eq(atom.strip_prefix("ns::name").unwrap(), Atom::new("")));
+        assert!(Atom::ptr_eq(atom.strip_suffix("ns::name").unwrap(), Atom::new("")));
     }
-}
 
-// PartialOrd str
-impl std::cmp::PartialOrd<str> for Atom {
-    fn partial_cmp(&self, other: &str) -> Option<std::cmp::Ordering> {
-        self.as_str().partial_cmp(other)
+    #[test]
+    fn record_new_in_test() {
+        let existing = Atom::new("#record existing");
+        let ((first, inner, last), outer_new) = Atom::record_new_in(|| {
+            let first = Atom::new("#record outer first");
+            let _ = Atom::new("#record existing");
+            // Initializing the ASCII table, if it isn't yet, records nothing.
+            let _ = Atom::new("r");
+            let (inner, inner_new) = Atom::record_new_in(|| Atom::new("#record inner"));
+            assert_eq!(inner_new, [inner]);
+            let last = Atom::from_string(String::from("#record outer last"));
+            (first, inner, last)
+        });
+        assert_eq!(outer_new, [first, last]);
+        assert!(!outer_new.contains(&inner));
+        assert!(!outer_new.contains(&existing));
     }
 
-    fn ge(&self, other: &str) -> bool {
-        self.as_str().ge(other)
+    #[test]
+    fn new_unique_test() {
+        let a = Atom::new_unique("x");
+        let b = Atom::new_unique("x");
+        assert!(!Atom::ptr_eq(a, b));
+        assert!(!Atom::ptr_eq(a, Atom::new("x")));
+        assert_eq!(a.as_str(), b.as_str());
+        assert_eq!(a.as_str(), "x");
     }
 
-    fn gt(&self, other: &str) -> bool {
-        self.as_str().gt(other)
+    #[test]
+    fn from_utf8_unchecked_test() {
+        let bytes = "unchecked \u{e9}\u{1f600}".as_bytes();
+        let unchecked = unsafe { Atom::from_utf8_unchecked(bytes) };
+        let checked = Atom::new(std::str::from_utf8(bytes).unwrap());
+        assert!(Atom::ptr_eq(unchecked, checked));
+        assert_eq!(unchecked.as_bytes(), bytes);
+        // Also covers the empty atom, the ASCII table and a string long
+        // enough for its key to skip the middle. Runs under Miri.
+        let long = "#unchecked \u{e9}".repeat(16);
+        for bytes in [&b""[..], b"u", long.as_bytes()] {
+            let unchecked = unsafe { Atom::from_utf8_unchecked(bytes) };
+            assert!(Atom::ptr_eq(unchecked, Atom::new(std::str::from_utf8(bytes).unwrap())));
+            assert_eq!(unchecked.as_bytes(), bytes);
+        }
     }
 
-    fn le(&self, other: &str) -> bool {
-        self.as_str().le(other)
+    #[test]
+    fn char_slice_test() {
+        let atom = Atom::new("h\u{e9}llo w\u{f6}rld \u{1f600}");
+        assert_eq!(atom.char_slice(0..5), Some("h\u{e9}llo"));
+        assert_eq!(atom.char_slice(6..11), Some("w\u{f6}rld"));
+        assert_eq!(atom.char_slice(12..13), Some("\u{1f600}"));
+        assert_eq!(atom.char_slice(13..13), Some(""));
+        assert_eq!(atom.char_slice(0..13), Some(atom.as_str()));
+        assert_eq!(atom.char_slice(12..14), None);
+        assert_eq!(atom.char_slice(14..14), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = atom.char_slice(3..1);
+        assert_eq!(reversed, None);
     }
 
-    fn lt(&self, other: &str) -> bool {
-        self.as_str().lt(other)
+    #[test]
+    fn key_eq_test() {
+        let middle_a = format!("{}{}{}", "h".repeat(ENDS_SIZE), "a", "t".repeat(ENDS_SIZE));
+        let middle_b = format!("{}{}{}", "h".repeat(ENDS_SIZE), "b", "t".repeat(ENDS_SIZE));
+        let a = Atom::new(&middle_a);
+        let b = Atom::new(&middle_b);
+        assert!(a.key_eq(Atom::new(&middle_a)));
+        assert_eq!(a.key_eq(b), HashMode::GLOBAL == HashMode::Ends);
+        assert!(!Atom::ptr_eq(a, b));
+        assert!(!Atom::new("key a").key_eq(Atom::new("key b")));
+        assert!(!Atom::new("key").key_eq(Atom::new("keys")));
     }
-}
 
-impl std::cmp::PartialOrd<Atom> for str {
-    fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
-        self.partial_cmp(other.as_str())
+    #[test]
+    fn filter_interned_test() {
+        let digits = [Atom::new("#filter 1"), Atom::new("#filter 22")];
+        let letters = Atom::new("#filter x");
+        let found = Atom::filter_interned(|string| {
+            string.starts_with("#filter") && string.contains(|c: char| c.is_ascii_digit())
+        });
+        assert_eq!(found.len(), 2);
+        assert!(digits.iter().all(|atom| found.contains(atom)));
+        assert!(!found.contains(&letters));
     }
 
-    fn ge(&self, other: &Atom) -> bool {
-        self.ge(other.as_str())
+    #[test]
+    fn ascii_table_test() {
+        let paren = Atom::new("(");
+        let general = Atom::try_new_in_set("(").unwrap();
+        assert!(Atom::ptr_eq(paren, general));
+        // The table is initialized now, so single ASCII characters must be
+        // served without taking the intern set locks.
+        let set_locks = lock_all_shards();
+        let atoms = [Atom::new("a"), Atom::new("\0"), Atom::new("\x7f")];
+        drop(set_locks);
+        assert_eq!(atoms.map(Atom::as_str), ["a", "\0", "\x7f"]);
+        assert!(Atom::ptr_eq(atoms[0], Atom::from_string(String::from("a"))));
+        assert!(!Atom::ptr_eq(Atom::new("\u{e9}"), Atom::new("e")));
     }
 
-    fn gt(&self, other: &Atom) -> bool {
-        self.gt(other.as_str())
+    #[test]
+    fn new_lowercased_test() {
+        let upper = Atom::new_lowercased("HTTP");
+        let lower = Atom::new_lowercased("http");
+        assert!(Atom::ptr_eq(upper, lower));
+        assert_eq!(upper.as_str(), "http");
+        assert!(Atom::ptr_eq(Atom::new_lowercased("\u{c9}T\u{c9}"), Atom::new("\u{e9}t\u{e9}")));
+        assert_eq!(Atom::new_lowercased("\u{1c5}"), "\u{1c6}");
     }
 
-    fn le(&self, other: &Atom) -> bool {
-        self.le(other.as_str())
+    #[test]
+    fn addr_test() {
+        let a = Atom::new("#addr a");
+        let b = Atom::new("#addr b");
+        assert_eq!(a.addr(), Atom::new("#addr a").addr());
+        assert_ne!(a.addr(), b.addr());
     }
 
-    fn lt(&self, other: &Atom) -> bool {
-        self.lt(other.as_str())
+    // Relies on the shared key of strings that differ only in the middle.
+    #[cfg(not(feature = "full-hash"))]
+    #[test]
+    fn scan_budget_test() {
+        let strings: Vec<String> = (0..64)
+            .map(|i| format!("{}{i:04}{}", "b".repeat(ENDS_SIZE), "e".repeat(ENDS_SIZE)))
+            .collect();
+        let atoms: Vec<Atom> = strings.iter().map(|string| Atom::new(string)).collect();
+        assert!(atoms.iter().all(|atom| atom.key_eq(atoms[0])));
+        let set_lock = lock_intern_set(atoms[0].key());
+        for (string, atom) in strings.iter().zip(atoms.iter().cloned()) {
+            let (found, scanned) = set_lock.find_counted(atom.key(), string);
+            assert_eq!(found, Some(atom));
+            assert!(scanned <= SCAN_BUDGET.load(Ordering::Relaxed) + 1);
+        }
     }
-}
 
-// PartialEq &str
-impl std::cmp::PartialEq<&str> for Atom {
-    fn eq(&self, other: &&str) -> bool {
-        self.as_str().eq(*other)
+    #[test]
+    fn from_borrowed_test() {
+        let expected = Atom::new("#from borrowed");
+        let mut string = String::from("#from borrowed");
+        let from_ref: Atom = (&string).into();
+        let from_mut: Atom = string.as_mut_str().into();
+        assert!(Atom::ptr_eq(from_ref, expected));
+        assert!(Atom::ptr_eq(from_mut, expected));
     }
 
-    fn ne(&self, other: &&str) -> bool {
-        self.as_str().ne(*other)
+    #[test]
+    fn split_to_boxed_test() {
+        let parts = Atom::new("a,b,c").split_to_boxed(',');
+        assert_eq!(&*parts, [Atom::new("a"), Atom::new("b"), Atom::new("c")]);
+        let empty = Atom::new("").split_to_boxed(',');
+        assert_eq!(&*empty, [Atom::new("")]);
+        let trailing = Atom::new("a,,").split_to_boxed(',');
+        assert_eq!(&*trailing, [Atom::new("a"), Atom::new(""), Atom::new("")]);
     }
-}
 
-impl std::cmp::PartialEq<Atom> for &str {
-    fn eq(&self, other: &Atom) -> bool {
-        (*self).eq(other.as_str())
+    #[test]
+    fn empty_atom_test() {
+        let empty = Atom::new("");
+        assert!(Atom::ptr_eq(empty, Atom::from_string(String::new())));
+        assert!(Atom::ptr_eq(empty, Atom::new("#empty").strip_prefix("#empty").unwrap()));
+        assert!(empty.is_empty());
+        assert_eq!(empty.as_str(), "");
+        let set_lock = lock_intern_set(empty.key());
+        let again = Atom::new("");
+        let found = set_lock.find(empty.key(), "");
+        drop(set_lock);
+        assert!(Atom::ptr_eq(empty, again));
+        assert_eq!(found, None);
     }
 
-    fn ne(&self, other: &Atom) -> bool {
-        (*self).ne(other.as_str())
+    #[test]
+    fn interned_iter_test() {
+        let lines = ["#iter a", "#iter b", "#iter a"];
+        let atoms: Vec<Atom> = lines.iter().interned().collect();
+        assert_eq!(atoms, lines.map(Atom::new));
+        let set: std::collections::HashSet<Atom> = lines.iter().interned().collect();
+        assert_eq!(set.len(), 2);
+        let owned: Vec<Atom> = vec![String::from("#iter b")].into_iter().interned().rev().collect();
+        assert!(Atom::ptr_eq(owned[0], atoms[1]));
     }
-}
 
-// PartialOrd &str
-impl std::cmp::PartialOrd<&str> for Atom {
-    fn partial_cmp(&self, other: &&str) -> Option<std::cmp::Ordering> {
-        self.as_str().partial_cmp(*other)
+    #[test]
+    fn natural_cmp_test() {
+        let names = ["file10.txt", "file2.txt", "file1.txt", "file02.txt", "file"];
+        let mut natural = names.map(Atom::new);
+        natural.sort_by(|a, b| a.cmp_with(*b, natural_cmp));
+        assert_eq!(natural.map(Atom::as_str), ["file", "file1.txt", "file2.txt", "file02.txt", "file10.txt"]);
+        let mut lexicographic = names.map(Atom::new);
+        lexicographic.sort();
+        assert_eq!(lexicographic.map(Atom::as_str), ["file", "file02.txt", "file1.txt", "file10.txt", "file2.txt"]);
+        assert_eq!(natural_cmp("a9b", "a10a"), std::cmp::Ordering::Less);
+        assert_eq!(natural_cmp("x007", "x007"), std::cmp::Ordering::Equal);
     }
 
-    fn ge(&self, other: &&str) -> bool {
-        self.as_str().ge(*other)
+    #[test]
+    fn intern_static_str_test() {
+        let a = intern_static_str("#static str");
+        let b = intern_static_str(&String::from("#static str"));
+        assert!(std::ptr::eq(a, b));
+        assert!(std::ptr::eq(a, Atom::new("#static str").as_str()));
     }
 
-    fn gt(&self, other: &&str) -> bool {
-        self.as_str().gt(*other)
+    #[test]
+    fn length_summary_test() {
+        let summary = LengthSummary::from_lengths((1..=20).rev().collect());
+        assert_eq!(summary.count, 20);
+        assert_eq!(summary.min, 1);
+        assert_eq!(summary.max, 20);
+        assert_eq!(summary.mean, 10.5);
+        assert_eq!(summary.median, 10);
+        assert_eq!(summary.p95, 19);
+        assert_eq!(LengthSummary::from_lengths(vec![]), LengthSummary::default());
+
+        let long = Atom::new(&"#length summary ".repeat(4096));
+        let _ = Atom::new("!");
+        let summary = Atom::length_summary();
+        assert!(summary.max >= long.len());
+        assert!(summary.min <= 1);
     }
 
-    fn le(&self, other: &&str) -> bool {
-        self.as_str().le(*other)
+    #[test]
+    fn debug_identity_test() {
+        let atom = Atom::new("#debug identity");
+        let output = format!("{:?}", atom.debug_identity());
+        assert!(output.contains("\"#debug identity\""));
+        assert!(output.contains(&format!("{:#x}", atom.addr())));
+        assert_eq!(output, format!("{:?}", Atom::new("#debug identity").debug_identity()));
+        assert_eq!(format!("{atom:?}"), "\"#debug identity\"");
     }
 
-    fn lt(&self, other: &&str) -> bool {
-        self.as_str().lt(*other)
+    #[test]
+    fn bytes_eq_test() {
+        let atom = Atom::new("#bytes eq");
+        assert!(atom.bytes_eq(b"#bytes eq"));
+        assert!(!atom.bytes_eq(b"#bytes EQ"));
+        assert!(!atom.bytes_eq(b"#bytes"));
+        assert!(!atom.bytes_eq(b"#bytes equal"));
+        assert!(Atom::new("").bytes_eq(b""));
     }
-}
 
-impl std::cmp::PartialOrd<Atom> for &str {
-    fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
-        (*self).partial_cmp(other.as_str())
+    #[test]
+    fn split_lines_test() {
+        let lines = Atom::new("one\r\ntwo\nthree\n").split_lines();
+        assert_eq!(lines, [Atom::new("one"), Atom::new("two"), Atom::new("three")]);
+        let lines = Atom::new("one\n\nthree").split_lines();
+        assert_eq!(lines, [Atom::new("one"), Atom::new(""), Atom::new("three")]);
+        assert!(Atom::new("").split_lines().is_empty());
     }
 
-    fn ge(&self, other: &Atom) -> bool {
-        (*self).ge(other.as_str())
+
+    #[test]
+    fn new_with_lines_test() {
+        let text = "fn main() {\n    // line\n}\nfn other() {\n    // line\n}\n";
+        let (whole, lines) = Atom::new_with_lines(text);
+        assert!(Atom::ptr_eq(whole, Atom::new(text)));
+        assert_eq!(lines.len(), 6);
+        assert!(Atom::ptr_eq(lines[1], lines[4]));
+        assert!(Atom::ptr_eq(lines[2], lines[5]));
+        assert_eq!(lines, whole.split_lines());
     }
 
-    fn gt(&self, other: &Atom) -> bool {
-        (*self).gt(other.as_str())
+    #[test]
+    fn longest_shortest_test() {
+        let atoms = ["bb", "ccc", "aaa", "d", "e"].map(Atom::new);
+        assert_eq!(Atom::longest_of(atoms.into_iter()), Some(Atom::new("aaa")));
+        assert_eq!(Atom::shortest_of(atoms.into_iter()), Some(Atom::new("d")));
+        assert_eq!(Atom::longest_of(std::iter::empty()), None);
+
+        let long = Atom::new(&"#longest ".repeat(100_000));
+        assert!(Atom::longest().unwrap().len() >= long.len());
+        assert_eq!(Atom::shortest().unwrap().len(), 1);
     }
 
-    fn le(&self, other: &Atom) -> bool {
-        (*self).le(other.as_str())
+    #[test]
+    fn into_bytes_test() {
+        let atom = Atom::new("#into bytes");
+        let boxed: Box<[u8]> = atom.into();
+        let arc: Arc<[u8]> = atom.into();
+        assert_eq!(&*boxed, b"#into bytes");
+        assert_eq!(&*arc, b"#into bytes");
     }
 
-    fn lt(&self, other: &Atom) -> bool {
-        (*self).lt(other.as_str())
+    #[test]
+    fn intern_test() {
+        let atom = Atom::intern("#intern");
+        assert!(Atom::ptr_eq(atom, Atom::intern(String::from("#intern"))));
+        assert!(Atom::ptr_eq(atom, Atom::intern(Cow::Borrowed("#intern"))));
+        assert!(Atom::ptr_eq(atom, Atom::intern(Box::<str>::from("#intern"))));
+        assert!(Atom::ptr_eq(atom, Atom::new("#intern")));
     }
-}
 
-// PartialEq String
-impl PartialEq<String> for Atom {
-    fn eq(&self, other: &String) -> bool {
-        self.as_str().eq(other)
+    /// Serializes tests that read or reset the global lock statistics.
+    #[cfg(feature = "instrument")]
+    static LOCK_STATS_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[cfg(feature = "instrument")]
+    #[test]
+    fn lock_stats_test() {
+        let _test_lock = LOCK_STATS_TEST_LOCK.lock().unwrap();
+        let before = Atom::lock_stats();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || {
+            let set_lock = lock_intern_set(AtomKey::from_str("#lock stats"));
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            drop(set_lock);
+        });
+        locked_rx.recv().unwrap();
+        let _ = Atom::new("#lock stats");
+        holder.join().unwrap();
+        let after = Atom::lock_stats();
+        assert!(after.acquisitions >= before.acquisitions + 2);
+        assert!(after.total_wait > before.total_wait);
+        assert!(after.max_wait >= std::time::Duration::from_millis(10));
     }
 
-    fn ne(&self, other: &String) -> bool {
-        self.as_str().ne(other)
+    #[test]
+    fn join_paths_test() {
+        let parts = [Atom::new("/usr/bin"), Atom::new("/bin")];
+        let joined = Atom::join_paths(&parts);
+        #[cfg(windows)]
+        assert_eq!(joined, "/usr/bin;/bin");
+        #[cfg(not(windows))]
+        assert_eq!(joined, "/usr/bin:/bin");
+        assert!(Atom::ptr_eq(Atom::join_paths(&[]), Atom::new("")));
+        assert!(Atom::ptr_eq(Atom::join_paths(&parts[..1]), parts[0]));
+    }
+
+    #[cfg(not(feature = "full-hash"))]
+    #[test]
+    fn bucket_sizes_test() {
+        let colliding: Vec<Atom> = (0..12)
+            .map(|i| Atom::new(&format!("{}{i:02}{}", "s".repeat(ENDS_SIZE), "z".repeat(ENDS_SIZE))))
+            .collect();
+        let set_lock = lock_intern_set(colliding[0].key());
+        let sizes = set_lock.bucket_sizes();
+        assert_eq!(sizes.values().sum::<usize>(), set_lock.atoms().count());
+        drop(set_lock);
+        assert_eq!(sizes[&colliding[0].key()], colliding.len());
+        assert!(Atom::bucket_sizes().any(|(key, size)| key == colliding[0].key() && size == colliding.len()));
     }
-}
 
-impl PartialEq<Atom> for String {
-    fn eq(&self, other: &Atom) -> bool {
-        self.eq(other.as_str())
+    #[test]
+    fn split_variants_test() {
+        let atom = Atom::new("a=b=c");
+        let parts: Vec<Atom> = atom.splitn(2, '=').collect();
+        assert_eq!(parts, [Atom::new("a"), Atom::new("b=c")]);
+        let parts: Vec<Atom> = atom.rsplitn(2, '=').collect();
+        assert_eq!(parts, [Atom::new("c"), Atom::new("a=b")]);
+        let parts: Vec<Atom> = Atom::new("a;b;").split_terminator(';').collect();
+        assert_eq!(parts, [Atom::new("a"), Atom::new("b")]);
+        let parts: Vec<Atom> = Atom::new("a;;").split_terminator(';').collect();
+        assert_eq!(parts, [Atom::new("a"), Atom::new("")]);
     }
 
-    fn ne(&self, other: &Atom) -> bool {
-        self.ne(other.as_str())
+    atom_newtype!(SymbolName);
+    atom_newtype! {
+        /// A type name.
+        pub(crate) TypeName
     }
-}
 
-// PartialOrd String
-impl PartialOrd<String> for Atom {
-    fn partial_cmp(&self, other: &String) -> Option<std::cmp::Ordering> {
-        self.partial_cmp(other.as_str())
+    #[test]
+    fn atom_macro_test() {
+        let atoms: Vec<Atom> = (0..4).map(|_| atom!("#atom macro")).collect();
+        assert!(atoms.iter().all(|&atom| Atom::ptr_eq(atom, Atom::new("#atom macro"))));
+        assert!(Atom::ptr_eq(atom!("#atom macro"), atoms[0]));
+        assert_eq!(atom!(""), Atom::new(""));
     }
 
-    fn ge(&self, other: &String) -> bool {
-        self.ge(other.as_str())
+    #[test]
+    fn atom_newtype_test() {
+        let name = SymbolName::new("#newtype");
+        assert_eq!(name.to_string(), "#newtype");
+        assert_eq!(format!("{name:?}"), "SymbolName(\"#newtype\")");
+        assert_eq!(name, SymbolName::from("#newtype"));
+        assert_eq!(name, "#newtype");
+        assert!(name < SymbolName::new("#newtype2"));
+        assert_eq!(name.len(), 8);
+        assert!(Atom::ptr_eq(name.atom(), Atom::new("#newtype")));
+        let type_name: TypeName = Atom::from(name).into();
+        assert_eq!(&*type_name, "#newtype");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let atoms = vec![Atom::new("#serde a"), Atom::new("#serde \"b\"")];
+        let json = serde_json::to_string(&atoms).unwrap();
+        assert_eq!(json, r##"["#serde a","#serde \"b\""]"##);
+        let borrowed: Vec<Atom> = serde_json::from_str(&json).unwrap();
+        assert_eq!(borrowed, atoms);
+        let owned: Vec<Atom> = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(owned, atoms);
     }
 
-    fn gt(&self, other: &String) -> bool {
-        self.gt(other.as_str())
+    #[cfg(feature = "serde")]
+    #[test]
+    fn stats_serde_test() {
+        let summary = LengthSummary::from_lengths(vec![1, 2, 3]);
+        let json: serde_json::Value = serde_json::to_value(summary).unwrap();
+        assert_eq!(json["count"], 3);
+        assert_eq!(json["min"], 1);
+        assert_eq!(json["max"], 3);
+        assert_eq!(json["mean"], 2.0);
+        assert_eq!(json["median"], 2);
+        assert!(json.get("p95").is_some());
+        #[cfg(feature = "instrument")]
+        {
+            let stats = LockStats {
+                total_wait: std::time::Duration::from_millis(3),
+                max_wait: std::time::Duration::from_millis(2),
+                acquisitions: 7,
+            };
+            let json: serde_json::Value = serde_json::to_value(stats).unwrap();
+            assert_eq!(json["acquisitions"], 7);
+            assert_eq!(json["max_wait"]["nanos"], 2_000_000);
+            assert!(json.get("total_wait").is_some());
+        }
     }
 
-    fn le(&self, other: &String) -> bool {
-        self.le(other.as_str())
+    #[cfg(feature = "regex")]
+    #[test]
+    fn intern_matches_test() {
+        let re = regex::Regex::new(r"#\w+").unwrap();
+        let atoms = Atom::intern_matches("#GET /a by #alice, #GET /b by #bob; #GET", &re);
+        assert_eq!(atoms, ["#GET", "#alice", "#GET", "#bob", "#GET"].map(Atom::new));
+        assert!(Atom::ptr_eq(atoms[0], atoms[2]));
+        assert!(Atom::ptr_eq(atoms[0], atoms[4]));
+        assert!(Atom::intern_matches("no matches", &re).is_empty());
     }
 
-    fn lt(&self, other: &String) -> bool {
-        self.lt(other.as_str())
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn graphemes_test() {
+        // "e" + combining acute accent, and a family emoji joined by ZWJs.
+        let atom = Atom::new("ne\u{301}e \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}!");
+        assert_eq!(atom.chars().count(), 11);
+        assert_eq!(atom.grapheme_count(), 6);
+        assert_eq!(atom.truncate_graphemes(2), "ne\u{301}");
+        assert_eq!(atom.truncate_graphemes(5), "ne\u{301}e \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}");
+        assert!(Atom::ptr_eq(atom.truncate_graphemes(6), atom));
+        assert!(Atom::ptr_eq(atom.truncate_graphemes(100), atom));
+        assert!(atom.truncate_graphemes(0).is_empty());
+    }
+
+    #[cfg(feature = "instrument")]
+    #[test]
+    fn reset_stats_test() {
+        let _test_lock = LOCK_STATS_TEST_LOCK.lock().unwrap();
+        let atom = Atom::new("#reset stats");
+        // Holding the set locks keeps other threads from recording an
+        // acquisition between resetting and reading the stats.
+        let set_locks = lock_all_shards();
+        Atom::reset_stats();
+        let stats = Atom::lock_stats();
+        drop(set_locks);
+        assert_eq!(stats, LockStats::default());
+        assert!(Atom::ptr_eq(atom, Atom::new("#reset stats")));
+        assert!(Atom::lock_stats().acquisitions >= 1);
     }
-}
 
-impl PartialOrd<Atom> for String {
-    fn partial_cmp(&self, other: &Atom) -> Option<std::cmp::Ordering> {
-        self.as_str().partial_cmp(other.as_str())
+    #[test]
+    fn new_with_test() {
+        fn trim(path: &str) -> &str {
+            path.trim_end_matches('/')
+        }
+        let eq = |lhs: &str, rhs: &str| {
+            // `eq` runs without the lock, so it may intern.
+            let _ = Atom::new_with(trim(lhs), AtomKey::from_str("#new_with eq"), str::eq);
+            trim(lhs) == trim(rhs)
+        };
+        let slash = Atom::new_with("#new_with/", AtomKey::from_str("#new_with"), eq);
+        let plain = Atom::new_with("#new_with", AtomKey::from_str(trim("#new_with")), eq);
+        assert!(Atom::ptr_eq(slash, plain));
+        assert_eq!(plain.as_str(), "#new_with/");
+        let other = Atom::new_with("#new_with2/", AtomKey::from_str("#new_with2"), eq);
+        assert!(!Atom::ptr_eq(slash, other));
+        assert!(!Atom::ptr_eq(Atom::new("#new_with/"), slash));
     }
 
-    fn ge(&self, other: &Atom) -> bool {
-        self.as_str().eq(other.as_str())
+    #[test]
+    fn from_chars_exact_test() {
+        let source = "#from chars \u{e9}\u{1f600}";
+        let upper = || source.chars().map(|c| c.to_ascii_uppercase());
+        let len = upper().map(char::len_utf8).sum();
+        let atom = Atom::from_chars_exact(len, upper());
+        assert!(Atom::ptr_eq(atom, Atom::new(&upper().collect::<String>())));
+        assert!(Atom::ptr_eq(Atom::from_chars_exact(len, upper()), atom));
+        assert!(Atom::ptr_eq(Atom::from_chars_exact(1, "a".chars()), Atom::new("a")));
+        assert!(Atom::ptr_eq(Atom::from_chars_exact(0, "".chars()), Atom::new("")));
     }
 
-    fn gt(&self, other: &Atom) -> bool {
-        self.as_str().gt(other.as_str())
+    #[test]
+    #[should_panic]
+    fn from_chars_exact_short_test() {
+        let _ = Atom::from_chars_exact(5, "#abc".chars());
     }
 
-    fn le(&self, other: &Atom) -> bool {
-        self.as_str().le(other.as_str())
+    #[test]
+    #[should_panic]
+    fn from_chars_exact_long_test() {
+        let _ = Atom::from_chars_exact(3, "#abc".chars());
     }
 
-    fn lt(&self, other: &Atom) -> bool {
-        self.as_str().lt(other.as_str())
+    #[test]
+    fn get_test() {
+        assert_eq!(Atom::get("#get missing"), None);
+        let atom = Atom::new("#get present");
+        assert_eq!(Atom::get("#get present"), Some(atom));
+        assert_eq!(Atom::get("#get missing"), None);
+        assert_eq!(Atom::get(""), Some(Atom::new("")));
+        assert_eq!(Atom::get("g"), Some(Atom::new("g")));
     }
-}
 
-impl std::ops::Deref for Atom {
-    type Target = str;
-    #[inline]
-    fn deref(&self) -> &Self::Target {
-        self.as_str()
+    #[test]
+    fn layout_info_test() {
+        for len in [2, 7, 8, 9, 31, 100] {
+            let atom = Atom::new(&format!("#{}", "l".repeat(len - 1)));
+            let info = atom.layout_info();
+            assert_eq!(info.addr, atom.addr());
+            assert_eq!(info.len, len);
+            let end = info.string_offset.unwrap() + info.len;
+            assert_eq!(end.next_multiple_of(info.align), info.size);
+        }
+        let leaked = Atom::from_string(String::from("#layout info leaked"));
+        assert_eq!(leaked.layout_info().string_offset, None);
+        let mut spare = String::with_capacity(64);
+        spare.push_str("#layout info spare");
+        assert!(Atom::from_string(spare).layout_info().string_offset.is_some());
+        assert_eq!(Atom::new("").layout_info().string_offset, None);
     }
-}
 
-impl AsRef<str> for Atom {
-    #[inline]
-    fn as_ref(&self) -> &str {
-        self.as_str()
+    #[test]
+    fn try_new_test() {
+        let atom = Atom::try_new("#try new").unwrap();
+        assert!(Atom::ptr_eq(atom, Atom::new("#try new")));
+        assert_eq!(Atom::try_new(""), Ok(Atom::new("")));
+        assert_eq!(AtomInner::try_layout(usize::MAX), None);
+        let err = AtomAllocError { len: usize::MAX };
+        assert_eq!(err.requested_len(), usize::MAX);
+        assert!(err.to_string().contains(&usize::MAX.to_string()));
     }
-}
 
-impl AsRef<Path> for Atom {
-    #[inline]
-    fn as_ref(&self) -> &Path {
-        self.as_path()
+    #[test]
+    fn read_len_prefixed_test() {
+        use std::io::{Cursor, ErrorKind};
+        fn framed(bytes: &[u8]) -> Vec<u8> {
+            let mut buf = (bytes.len() as u64).to_le_bytes().to_vec();
+            buf.extend_from_slice(bytes);
+            buf
+        }
+        let mut input = framed(b"#read prefixed");
+        input.extend(framed(b""));
+        let mut cursor = Cursor::new(input);
+        let atom = Atom::read_len_prefixed(&mut cursor).unwrap();
+        assert!(Atom::ptr_eq(atom, Atom::new("#read prefixed")));
+        assert!(Atom::read_len_prefixed(&mut cursor).unwrap().is_empty());
+        assert_eq!(Atom::read_len_prefixed(&mut cursor).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let mut truncated = framed(b"#read truncated");
+        truncated.truncate(12);
+        assert_eq!(Atom::read_len_prefixed(&mut Cursor::new(truncated)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let invalid = framed(b"#read \xff");
+        assert_eq!(Atom::read_len_prefixed(&mut Cursor::new(invalid)).unwrap_err().kind(), ErrorKind::InvalidData);
     }
-}
 
-impl std::borrow::Borrow<str> for Atom {
-    fn borrow(&self) -> &str {
-        self.as_str()
+    #[test]
+    fn new_in_namespace_test() {
+        let table = Atom::new_in_namespace(1, "#namespace id");
+        let column = Atom::new_in_namespace(2, "#namespace id");
+        assert_eq!(table.as_str(), column.as_str());
+        assert!(!Atom::ptr_eq(table, column));
+        assert!(!Atom::ptr_eq(table, Atom::new("#namespace id")));
+        assert!(Atom::ptr_eq(table, Atom::new_in_namespace(1, "#namespace id")));
+        assert!(!Atom::ptr_eq(Atom::new_in_namespace(1, ""), Atom::new_in_namespace(2, "")));
     }
-}
 
-impl std::borrow::Borrow<Path> for Atom {
-    fn borrow(&self) -> &Path {
-        self.as_path()
+    #[test]
+    fn new_static_test() {
+        static STRING: &str = "#new static";
+        let atom = Atom::new_static(STRING);
+        assert!(std::ptr::eq(atom.as_str(), STRING));
+        assert!(Atom::ptr_eq(atom, Atom::new("#new static")));
+        assert_eq!(atom.layout_info().string_offset, None);
+        let existing = Atom::new("#new static existing");
+        assert!(Atom::ptr_eq(Atom::new_static("#new static existing"), existing));
+        assert!(Atom::ptr_eq(Atom::new_static("s"), Atom::new("s")));
     }
-}
 
-impl From<Atom> for String {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        value.as_str().to_owned()
+    #[test]
+    fn intern_count_test() {
+        // Other tests add to and remove from the global set concurrently, so
+        // exact counts are checked on a local set.
+        let mut set = InternSet::default();
+        let atoms = [Atom::new_unique("#intern count a"), Atom::new_unique("#intern count bc")];
+        for atom in atoms {
+            set.insert(atom);
+        }
+        assert_eq!(set.atoms().count(), 2);
+        assert_eq!(set.bytes, atoms[0].len() + atoms[1].len());
+        set.remove_many(&[atoms[0]].into_iter().collect());
+        assert_eq!(set.atoms().count(), 1);
+        assert_eq!(set.bytes, atoms[1].len());
+        let atom = Atom::new("#intern count");
+        assert!(Atom::intern_count() >= 1);
+        assert!(Atom::total_interned_bytes() >= atom.len());
     }
-}
 
-impl From<Atom> for Cow<'static, str> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Cow::Borrowed(value.as_str())
+    #[test]
+    fn borrow_test() {
+        // `Atom::borrow` fits `Fn(&'a Atom) -> &'a str` directly, where
+        // `Atom::as_str` (taking `self` by value) would not.
+        fn pick<'a>(items: &'a [Atom], mut pred: impl FnMut(&'a str) -> bool) -> Option<&'a str> {
+            items.iter().map(Atom::borrow).find(|string| pred(string))
+        }
+        let atoms = [Atom::new("#borrow a"), Atom::new("#borrow b")];
+        let mut seen = Vec::new();
+        let found = pick(&atoms, |string| {
+            seen.push(string);
+            string.ends_with('b')
+        });
+        assert_eq!(found, Some("#borrow b"));
+        assert_eq!(seen, ["#borrow a", "#borrow b"]);
+        assert!(std::ptr::eq(atoms[0].borrow(), atoms[0].as_str()));
     }
-}
 
-impl From<Atom> for Box<str> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Box::from(value.as_str())
+    #[test]
+    fn iter_all_test() {
+        let atom = Atom::new("#iter all");
+        let all: Vec<Atom> = Atom::iter_all().collect();
+        assert!(all.contains(&atom));
+        assert!(all.contains(&Atom::new("a")));
+        assert!(!all.contains(&Atom::new("")));
+        let unique: std::collections::HashSet<Atom> = all.iter().copied().collect();
+        assert_eq!(unique.len(), all.len());
     }
-}
 
-impl From<Atom> for Rc<str> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Rc::from(value.as_str())
+    #[test]
+    fn str_cmp_test() {
+        let atom = Atom::new("#cmp m");
+        for (other, ordering) in [("#cmp a", std::cmp::Ordering::Greater), ("#cmp m", std::cmp::Ordering::Equal), ("#cmp z", std::cmp::Ordering::Less)] {
+            let string = String::from(other);
+            assert_eq!(atom.partial_cmp(&string), Some(ordering));
+            assert_eq!(atom < string, ordering.is_lt());
+            assert_eq!(atom <= string, ordering.is_le());
+            assert_eq!(atom > string, ordering.is_gt());
+            assert_eq!(atom >= string, ordering.is_ge());
+            assert_eq!(atom == string, ordering.is_eq());
+            assert_eq!(string.partial_cmp(&atom), Some(ordering.reverse()));
+            assert_eq!(string < atom, ordering.is_gt());
+            assert_eq!(string <= atom, ordering.is_ge());
+            assert_eq!(string > atom, ordering.is_lt());
+            assert_eq!(string >= atom, ordering.is_le());
+            assert_eq!(string == atom, ordering.is_eq());
+            assert_eq!(atom < other, ordering.is_lt());
+            assert_eq!(atom >= other, ordering.is_ge());
+            assert_eq!(other <= atom, ordering.is_ge());
+            assert_eq!(other > atom, ordering.is_lt());
+            assert_eq!(*other >= atom, ordering.is_le());
+            assert_eq!(atom.partial_cmp(other), Some(ordering));
+        }
     }
-}
 
-impl From<Atom> for Arc<str> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Arc::from(value.as_str())
+
+    #[test]
+    fn repeat_char_test() {
+        let dashes = Atom::repeat_char('#', 4);
+        assert_eq!(dashes, "####");
+        assert!(Atom::ptr_eq(dashes, Atom::repeat_char('#', 4)));
+        assert!(Atom::ptr_eq(dashes, Atom::new("####")));
+        let wide = Atom::repeat_char('\u{2500}', 3);
+        assert_eq!(wide, "\u{2500}\u{2500}\u{2500}");
+        assert_eq!(wide.len(), 9);
+        assert!(Atom::ptr_eq(wide, Atom::repeat_char('\u{2500}', 3)));
+        assert!(Atom::ptr_eq(Atom::repeat_char('x', 0), Atom::new("")));
+        assert!(Atom::ptr_eq(Atom::repeat_char('x', 1), Atom::new("x")));
     }
-}
 
-impl From<Atom> for Vec<u8> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Self::from(value.as_bytes())
+    #[test]
+    fn poisoned_lock_test() {
+        let existing = Atom::new("#poison");
+        let key = existing.key();
+        let result = std::panic::catch_unwind(|| {
+            let _set_lock = lock_intern_set(key);
+            panic!("panicked under the lock");
+        });
+        assert!(result.is_err());
+        assert!(INTERN_SET[(key.hash % SHARD_COUNT as u64) as usize].is_poisoned());
+        assert!(Atom::ptr_eq(Atom::new("#poison"), existing));
+        assert_eq!(Atom::new("#poison after"), "#poison after");
+        assert_eq!(Atom::get("#poison new"), None);
     }
-}
 
-impl From<Atom> for Vec<char> {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        Self::from_iter(value.chars())
+    #[test]
+    fn try_slice_test() {
+        // "\u{e9}" is two bytes, spanning 1..3.
+        let atom = Atom::new("#\u{e9}t\u{e9}");
+        assert_eq!(atom.try_slice(0..3), Ok("#\u{e9}"));
+        assert_eq!(atom.try_slice(3..=3), Ok("t"));
+        assert_eq!(atom.try_slice(..), Ok(atom.as_str()));
+        assert_eq!(atom.try_slice(6..), Ok(""));
+        assert_eq!(atom.try_slice(2..4), Err(SliceError::NotCharBoundary { index: 2 }));
+        assert_eq!(atom.try_slice(..5), Err(SliceError::NotCharBoundary { index: 5 }));
+        assert_eq!(atom.try_slice(3..7), Err(SliceError::OutOfBounds { index: 7, len: 6 }));
+        assert_eq!(atom.try_slice(..=usize::MAX), Err(SliceError::OutOfBounds { index: usize::MAX, len: 6 }));
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = atom.try_slice(3..1);
+        assert_eq!(reversed, Err(SliceError::StartAfterEnd { start: 3, end: 1 }));
     }
-}
 
-impl From<Atom> for &'static str {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        value.as_str()
+    #[test]
+    fn normalized_path_test() {
+        let canonical = Atom::new_normalized_path("#paths/a/b/../c");
+        assert_eq!(canonical, "#paths/a/c");
+        assert!(Atom::ptr_eq(canonical, Atom::new("#paths/a/c")));
+        assert!(Atom::ptr_eq(canonical, Atom::new_normalized_path("#paths/a/c")));
+        assert!(Atom::ptr_eq(canonical, Atom::new_normalized_path("./#paths//a/./c/")));
+        assert!(Atom::ptr_eq(canonical, Atom::new_normalized_path("#paths/a/b/d/../../c")));
+        assert_eq!(Atom::new_normalized_path("/#paths/../../a"), "/a");
+        assert_eq!(Atom::new_normalized_path("../#paths/../../a"), "../../a");
+        assert_eq!(Atom::new_normalized_path("#paths/.."), ".");
+        assert_eq!(Atom::new_normalized_path(""), ".");
+        assert_eq!(Atom::new_normalized_path("//./"), "/");
     }
-}
 
-impl From<Atom> for PathBuf {
-    #[inline]
-    fn from(value: Atom) -> Self {
-        PathBuf::from(value.as_str())
+    #[test]
+    fn from_str_test() {
+        assert_eq!("#parse".parse::<Atom>().unwrap(), Atom::new("#parse"));
+        assert!(Atom::ptr_eq("#parse".parse().unwrap(), Atom::new("#parse")));
     }
-}
 
-impl From<&str> for Atom {
-    #[inline]
-    fn from(value: &str) -> Self {
-        Atom::new(value)
+    #[test]
+    fn default_test() {
+        assert_eq!(Atom::default(), Atom::empty());
+        assert!(Atom::ptr_eq(Atom::default(), Atom::empty()));
+        assert!(Atom::ptr_eq(Atom::empty(), Atom::new("")));
+        assert!(Atom::empty().is_empty());
     }
-}
 
-impl From<String> for Atom {
-    #[inline]
-    fn from(value: String) -> Self {
-        Atom::new(value.as_str())
+    #[test]
+    fn split_at_char_test() {
+        let atom = Atom::new("#héllo wörld");
+        let (head, tail) = atom.split_at_char(3).unwrap();
+        assert_eq!(head, "#hé");
+        assert_eq!(tail, "llo wörld");
+        assert!(Atom::ptr_eq(tail, Atom::new("llo wörld")));
+        let (head, tail) = atom.split_at_char(12).unwrap();
+        assert!(Atom::ptr_eq(head, atom));
+        assert!(tail.is_empty());
+        assert_eq!(atom.split_at_char(13), None);
+        assert_eq!(atom.split_at_char(0), Some((Atom::empty(), atom)));
     }
-}
 
-impl From<Box<str>> for Atom {
-    #[inline]
-    fn from(value: Box<str>) -> Self {
-        Atom::new(&value)
+    #[test]
+    fn from_utf8_test() {
+        let atom = Atom::from_utf8("#utf8 é".as_bytes()).unwrap();
+        assert!(Atom::ptr_eq(atom, Atom::new("#utf8 é")));
+        assert!(Atom::from_utf8(b"#utf8 \xff").is_err());
+        assert_eq!(Atom::from_utf8_lossy(b"#utf8 \xff!"), "#utf8 \u{FFFD}!");
+        assert!(Atom::ptr_eq(Atom::from_utf8_lossy("#utf8 é".as_bytes()), atom));
     }
-}
 
-impl From<Rc<str>> for Atom {
-    #[inline]
-    fn from(value: Rc<str>) -> Self {
-        Atom::new(&value)
+    #[test]
+    fn prefix_compressed_table_test() {
+        use std::io::{Cursor, ErrorKind};
+        let mut paths: Vec<Atom> = (0..32)
+            .map(|i| Atom::new(&format!("#table/src/module_{}/file_{i}.rs", i / 8)))
+            .collect();
+        paths.push(Atom::new("#table/src/é"));
+        paths.push(Atom::empty());
+        paths.push(Atom::new("#table/src/module_0/file_0.rs"));
+        let mut compressed = Vec::new();
+        Atom::dump_table_prefix_compressed(&paths, &mut compressed).unwrap();
+        let flat_len: usize = paths.iter().map(|atom| 8 + atom.len()).sum();
+        assert!(compressed.len() < flat_len / 2);
+        let loaded = Atom::load_table_prefix_compressed(&mut Cursor::new(&compressed)).unwrap();
+        assert_eq!(loaded.len(), paths.len());
+        assert!(loaded.iter().zip(&paths).all(|(&lhs, &rhs)| Atom::ptr_eq(lhs, rhs)));
+        let truncated = &compressed[..compressed.len() - 1];
+        assert_eq!(Atom::load_table_prefix_compressed(&mut Cursor::new(truncated)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        // One atom that claims to share a byte with the (empty) previous one.
+        let bogus = [1u8, 1, 0];
+        assert_eq!(Atom::load_table_prefix_compressed(&mut Cursor::new(bogus)).unwrap_err().kind(), ErrorKind::InvalidData);
+        let overlong = [0xffu8; 11];
+        assert_eq!(Atom::load_table_prefix_compressed(&mut Cursor::new(overlong)).unwrap_err().kind(), ErrorKind::InvalidData);
     }
-}
 
-impl From<Arc<str>> for Atom {
-    #[inline]
-    fn from(value: Arc<str>) -> Self {
-        Atom::new(&value)
+    #[test]
+    fn matches_any_test() {
+        let route = Atom::new("#route/api/users.json");
+        assert!(route.starts_with_any(&["#route/static", "#route/api"]));
+        assert!(!route.starts_with_any(&["#route/static", "api"]));
+        assert!(!route.starts_with_any(&[]));
+        assert!(route.ends_with_any(&[".xml", ".json"]));
+        assert!(!route.ends_with_any(&[".xml", "users"]));
+        let set = [Atom::new("#route/a"), Atom::new("#route/api/users.json")];
+        assert!(route.matches_any_atom(&set));
+        assert!(!Atom::new("#route/b").matches_any_atom(&set));
+        assert!(!Atom::new_unique("#route/a").matches_any_atom(&set));
     }
-}
 
-impl<'a> From<Cow<'a, str>> for Atom {
-    #[inline]
-    fn from(value: Cow<'a, str>) -> Self {
-        Atom::new(&value)
+    #[test]
+    fn reserve_test() {
+        // A local set, so the global shards aren't grown for every test.
+        let mut set = InternSet::default();
+        set.reserve(1000);
+        assert!(set.buckets.capacity() >= 1000);
+        let atom = Atom::new_unique("#reserve");
+        set.insert(atom);
+        set.shrink_to_fit();
+        assert!(set.buckets.capacity() < 1000);
+        assert_eq!(set.find(atom.key(), "#reserve"), Some(atom));
     }
-}
 
-impl std::fmt::Display for Atom {
-    #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.as_str())
+    #[test]
+    fn new_suffixed_test() {
+        let base = Atom::new("#suffixed.a.b");
+        let atom = Atom::new_suffixed(base, ".c");
+        assert_eq!(atom, Atom::new("#suffixed.a.b.c"));
+        assert!(Atom::ptr_eq(atom, Atom::new("#suffixed.a.b.c")));
+        assert_eq!(atom.strip_prefix(base.as_str()), Some(Atom::new(".c")));
+        assert!(Atom::ptr_eq(Atom::new_suffixed(base, ""), base));
+        assert_eq!(Atom::new_suffixed(Atom::empty(), "#suffixed"), "#suffixed");
     }
-}
 
-impl std::fmt::Debug for Atom {
-    #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.as_str())
+    #[test]
+    fn ascii_ci_test() {
+        assert!(Atom::new("#Content-Type").eq_ignore_ascii_case("#content-TYPE"));
+        assert!(!Atom::new("#Content-Type").eq_ignore_ascii_case("#content_type"));
+        let first = Atom::new_ascii_ci("#CI Content-Type");
+        let lower = Atom::new_ascii_ci("#ci content-type");
+        assert!(Atom::ptr_eq(first, lower));
+        assert!(Atom::ptr_eq(first, Atom::new_ascii_ci("#CI CONTENT-TYPE")));
+        assert_eq!(lower.as_str(), "#CI Content-Type");
+        assert!(!Atom::ptr_eq(Atom::new_ascii_ci("#ci content-length"), first));
+        assert!(!Atom::ptr_eq(Atom::new("#CI Content-Type"), first));
+        let key = AtomKey::from_str("#ci content-type");
+        assert!(!Atom::ptr_eq(Atom::new_with("#CI Content-Type", key, str::eq_ignore_ascii_case), first));
+        // Only ASCII case is folded.
+        assert!(!Atom::ptr_eq(Atom::new_ascii_ci("#ci \u{c9}"), Atom::new_ascii_ci("#ci \u{e9}")));
     }
-}
 
-impl std::hash::Hash for Atom {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        unsafe {
-            // The key is deterministically derived from the
-            // immutable string, so we can just hash the key
-            // for fast hashing of Atom types.
-            self.inner.as_ref().key.hash(state);
+    #[test]
+    fn into_display_box_test() {
+        let boxed = Atom::new("#display box").into_display_box();
+        assert_eq!(boxed.to_string(), "#display box");
+        assert_eq!(std::mem::size_of_val(&*boxed), std::mem::size_of::<Atom>());
+    }
+
+    #[test]
+    fn concat_test() {
+        let atom = Atom::new("#concat");
+        let joined = atom.concat("::name");
+        assert!(Atom::ptr_eq(joined, Atom::new("#concat::name")));
+        assert!(Atom::ptr_eq(atom + "::name", joined));
+        assert!(Atom::ptr_eq(atom + "", atom));
+        assert_eq!(atom + "::é" + "!", "#concat::é!");
+    }
+
+    #[test]
+    fn write_sort_key_test() {
+        let mut atoms = ["#key b", "#key a", "#key", "#key \u{e9}", "#key z", "#key \u{1f600}", ""].map(Atom::new);
+        let mut keys = Vec::new();
+        let mut ranges = Vec::new();
+        for atom in atoms {
+            let start = keys.len();
+            atom.write_sort_key(&mut keys);
+            ranges.push((start..keys.len(), atom));
         }
+        ranges.sort_by(|(lhs, _), (rhs, _)| keys[lhs.clone()].cmp(&keys[rhs.clone()]));
+        atoms.sort();
+        assert!(ranges.iter().map(|(_, atom)| *atom).eq(atoms));
+    }
+
+    #[test]
+    fn self_check_test() {
+        // The global set is shared with concurrently running tests, so a
+        // local set is corrupted instead.
+        let original = Atom::new_unique("#self check");
+        let shard = (original.key().hash % SHARD_COUNT as u64) as usize;
+        let mut set = InternSet::default();
+        set.insert(original);
+        let mut reports = Vec::new();
+        set.check(shard, &mut reports);
+        assert_eq!(reports, []);
+        // Inject a duplicate of the atom, and an atom in the wrong bucket,
+        // bypassing `insert_with_key`.
+        let duplicate = Atom::new_unique("#self check");
+        let misplaced = Atom::new_unique("#self check misplaced");
+        let wrong_key = AtomKey { hash: original.key().hash, len: misplaced.len() };
+        set.buckets.entry(original.key()).or_default().atoms.push(duplicate);
+        set.buckets.entry(wrong_key).or_default().atoms.push(misplaced);
+        set.check(shard, &mut reports);
+        assert!(reports.contains(&CorruptionReport::Duplicate { first: original.addr(), second: duplicate.addr() }));
+        assert!(reports.contains(&CorruptionReport::WrongBucket { addr: misplaced.addr(), bucket: wrong_key, key: misplaced.key() }));
+        let recorded = original.len();
+        let counted = recorded + duplicate.len() + misplaced.len();
+        assert!(reports.contains(&CorruptionReport::ByteCount { shard, counted, recorded }));
+        reports.clear();
+        set.check((shard + 1) % SHARD_COUNT, &mut reports);
+        assert!(reports.contains(&CorruptionReport::WrongShard { addr: original.addr(), shard: (shard + 1) % SHARD_COUNT, expected: shard }));
+    }
+
+    #[test]
+    fn case_atom_test() {
+        let upper = Atom::new("#CASE \u{c9}T\u{c9}");
+        let lower = Atom::new("#case \u{e9}t\u{e9}");
+        let mixed = Atom::new("#Case \u{c9}t\u{e9}");
+        assert!(Atom::ptr_eq(upper.to_uppercase_atom(), upper));
+        assert!(Atom::ptr_eq(lower.to_lowercase_atom(), lower));
+        assert!(Atom::ptr_eq(mixed.to_uppercase_atom(), upper));
+        assert!(Atom::ptr_eq(mixed.to_lowercase_atom(), lower));
+        assert!(Atom::ptr_eq(upper.to_lowercase_atom(), lower));
+        assert_eq!(Atom::new("#stra\u{df}e").to_uppercase_atom(), "#STRASSE");
+        let unique = Atom::new_unique("#case unique");
+        assert!(Atom::ptr_eq(unique.to_lowercase_atom(), unique));
+    }
+
+    #[test]
+    fn sub_atom_test() {
+        let path = Atom::new("#sub/\u{e9}t\u{e9}.tar.gz");
+        assert!(Atom::ptr_eq(path.sub_atom(..), path));
+        assert!(Atom::ptr_eq(path.sub_atom(0..path.len()), path));
+        let ext = path.sub_atom(path.len() - 3..);
+        assert!(Atom::ptr_eq(ext, Atom::new(".gz")));
+        assert_eq!(path.sub_atom(5..=9), "\u{e9}t\u{e9}");
+        assert!(path.sub_atom(3..3).is_empty());
+        let unique = Atom::new_unique("#sub unique");
+        assert!(Atom::ptr_eq(unique.sub_atom(..), unique));
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_atom_char_boundary_test() {
+        let _ = Atom::new("#sub \u{e9}").sub_atom(..6);
+    }
+
+    #[test]
+    fn borrow_str_lookup_test() {
+        use std::hash::BuildHasher;
+        let mut map = HashMap::new();
+        map.insert(Atom::new("#borrow key"), 1);
+        assert_eq!(map.get("#borrow key"), Some(&1));
+        assert_eq!(map.get("#borrow other"), None);
+        let state = std::hash::RandomState::new();
+        assert_eq!(state.hash_one(Atom::new("#borrow key")), state.hash_one("#borrow key"));
     }
 }
 
-#[cfg(test)]
-mod tests {
+// The pinned sizes and offsets assume a 64-bit target.
+#[cfg(all(test, target_pointer_width = "64"))]
+mod layout_tests {
     use super::*;
-    
+    use std::mem::{align_of, offset_of, size_of};
+
+    const _: () = assert!(size_of::<Atom>() == size_of::<usize>());
+    const _: () = assert!(align_of::<Atom>() == align_of::<usize>());
+    const _: () = assert!(size_of::<Option<Atom>>() == size_of::<Atom>());
+    const _: () = assert!(size_of::<AtomKey>() == 16);
+    const _: () = assert!(offset_of!(AtomInner<()>, value) == 16);
+    const _: () = assert!(size_of::<AtomInner<ExternalBytes>>() == 32);
+
     #[test]
-    fn substring_test() {
-        let atom = Atom::new("0123456789");
-        assert_eq!(&atom[1..4], "123");
+    fn value_offset_test() {
+        let atom = Atom::new("layout value offset");
+        let fat_ptr = AtomInner::fatten(atom.inner, atom.len());
+        let value_offset = unsafe {
+            std::ptr::addr_of!((*fat_ptr.as_ptr()).value) as *const u8 as usize - atom.inner.as_ptr() as usize
+        };
+        assert_eq!(value_offset, offset_of!(AtomInner<()>, value));
+        assert_eq!(atom.as_ptr() as usize - atom.inner.as_ptr() as usize, value_offset);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn layout_size_test() {
+        assert_eq!(AtomInner::layout(0).size(), 16);
+        assert_eq!(AtomInner::layout(7).size(), 24);
+        assert_eq!(AtomInner::layout(8).size(), 24);
+        assert_eq!(AtomInner::layout(9).size(), 32);
+        assert_eq!(AtomInner::layout(0).align(), align_of::<AtomInner<()>>());
+    }
+}
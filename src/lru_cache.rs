@@ -0,0 +1,209 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    hash::{
+        BuildHasherDefault,
+        Hasher,
+    },
+};
+use crate::Atom;
+
+/// An [Atom] key compared by pointer and hashed by its precomputed
+/// [AtomKey](crate::AtomKey) hash, so lookups never touch the string.
+#[derive(Clone, Copy)]
+struct IdentityKey(Atom);
+
+impl std::cmp::PartialEq for IdentityKey {
+    fn eq(&self, other: &Self) -> bool {
+        Atom::ptr_eq(self.0, other.0)
+    }
+}
+
+impl std::cmp::Eq for IdentityKey {}
+
+impl std::hash::Hash for IdentityKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.hash());
+    }
+}
+
+/// Passes the precomputed hash written by [IdentityKey] through unchanged.
+#[derive(Default)]
+struct PassThroughHasher(u64);
+
+impl Hasher for PassThroughHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+}
+
+/// A cache of values keyed by [Atom], holding at most a fixed number of
+/// entries and evicting the least recently used entry when full.
+/// 
+/// Keys are compared by pointer and hashed by their precomputed
+/// [AtomKey](crate::AtomKey) hash, so lookups never hash or compare
+/// strings. Only the values are evicted; the atoms stay interned.
+pub struct AtomLruCache<V> {
+    capacity: usize,
+    /// The entries, with the tick of their last use.
+    entries: HashMap<IdentityKey, (V, u64), BuildHasherDefault<PassThroughHasher>>,
+    /// The keys ordered by the tick of their last use.
+    order: BTreeMap<u64, Atom>,
+    tick: u64,
+}
+
+impl<V> AtomLruCache<V> {
+    /// Creates an empty [AtomLruCache] that holds at most `capacity`
+    /// entries.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::default(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Inserts `value` for `atom`, marking it as the most recently used
+    /// entry, and returns the previous value for `atom`. If the cache is
+    /// full and `atom` isn't in it, the least recently used entry is
+    /// evicted first.
+    pub fn insert(&mut self, atom: Atom, value: V) -> Option<V> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let tick = self.next_tick();
+        if let Some((old_value, last_use)) = self.entries.insert(IdentityKey(atom), (value, tick)) {
+            self.order.remove(&last_use);
+            self.order.insert(tick, atom);
+            return Some(old_value);
+        }
+        if self.entries.len() > self.capacity
+            && let Some((_, evicted)) = self.order.pop_first() {
+            self.entries.remove(&IdentityKey(evicted));
+        }
+        self.order.insert(tick, atom);
+        None
+    }
+
+    /// Returns the value for `atom`, marking it as the most recently used
+    /// entry.
+    pub fn get(&mut self, atom: Atom) -> Option<&V> {
+        let tick = self.next_tick();
+        let (value, last_use) = self.entries.get_mut(&IdentityKey(atom))?;
+        self.order.remove(last_use);
+        self.order.insert(tick, atom);
+        *last_use = tick;
+        Some(value)
+    }
+
+    /// Returns the value for `atom` without marking it as used.
+    #[must_use]
+    pub fn peek(&self, atom: Atom) -> Option<&V> {
+        self.entries.get(&IdentityKey(atom)).map(|(value, _)| value)
+    }
+
+    /// Removes the entry for `atom`, returning its value.
+    pub fn remove(&mut self, atom: Atom) -> Option<V> {
+        let (value, last_use) = self.entries.remove(&IdentityKey(atom))?;
+        self.order.remove(&last_use);
+        Some(value)
+    }
+
+    /// Returns `true` if the cache holds an entry for `atom`.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, atom: Atom) -> bool {
+        self.entries.contains_key(&IdentityKey(atom))
+    }
+
+    /// Returns the number of entries.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of entries held at once.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<V: std::fmt::Debug> std::fmt::Debug for AtomLruCache<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.order.values().map(|&atom| (atom, self.peek(atom).unwrap())))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction_test() {
+        let [a, b, c] = ["#lru a", "#lru b", "#lru c"].map(Atom::new);
+        let mut cache = AtomLruCache::new(2);
+        assert_eq!(cache.insert(a, 1), None);
+        assert_eq!(cache.insert(b, 2), None);
+        assert_eq!(cache.get(a), Some(&1));
+        assert_eq!(cache.insert(c, 3), None);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(b));
+        assert_eq!(cache.peek(a), Some(&1));
+        assert_eq!(cache.peek(c), Some(&3));
+        // Peeking doesn't count as a use, so `a` is evicted next.
+        assert_eq!(cache.insert(b, 4), None);
+        assert!(!cache.contains(a));
+        assert_eq!(format!("{cache:?}"), r##"{"#lru c": 3, "#lru b": 4}"##);
+    }
+
+    #[test]
+    fn insert_test() {
+        let a = Atom::new("#lru insert");
+        let mut cache = AtomLruCache::new(2);
+        assert_eq!(cache.insert(a, "x"), None);
+        assert_eq!(cache.insert(a, "y"), Some("x"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(Atom::new("#lru insert")), Some(&"y"));
+        assert_eq!(cache.get(Atom::new_unique("#lru insert")), None);
+        assert_eq!(cache.remove(a), Some("y"));
+        assert!(cache.is_empty());
+        let mut empty = AtomLruCache::new(0);
+        assert_eq!(empty.insert(a, 1), None);
+        assert!(empty.is_empty());
+    }
+}
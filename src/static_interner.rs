@@ -0,0 +1,102 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+use crate::{Atom, AtomKey};
+
+/// A fixed-capacity set of up to `N` atoms stored inline.
+/// 
+/// Lookups scan the entries linearly, so this is meant for small, fixed
+/// vocabularies. The set itself never allocates; the atoms it holds are
+/// regular global [Atom]s.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticInterner<const N: usize> {
+    entries: [Option<(AtomKey, Atom)>; N],
+    len: usize,
+}
+
+impl<const N: usize> StaticInterner<N> {
+    /// Creates an empty [StaticInterner].
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the [Atom] for `string` if it is in the set.
+    #[must_use]
+    pub fn get(&self, string: &str) -> Option<Atom> {
+        let key = AtomKey::from_str(string);
+        self.entries[..self.len].iter()
+            .flatten()
+            .find(|(entry_key, atom)| *entry_key == key && atom.as_str() == string)
+            .map(|&(_, atom)| atom)
+    }
+
+    /// Returns the [Atom] for `string`, adding it to the set if it isn't
+    /// present. Returns [None] if `string` isn't present and the set is
+    /// full.
+    pub fn try_intern(&mut self, string: &str) -> Option<Atom> {
+        if let Some(atom) = self.get(string) {
+            return Some(atom);
+        }
+        if self.len == N {
+            return None;
+        }
+        let atom = Atom::new(string);
+        self.entries[self.len] = Some((atom.key(), atom));
+        self.len += 1;
+        Some(atom)
+    }
+
+    /// Returns the number of atoms in the set.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of atoms the set can hold.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Default for StaticInterner<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_interner_test() {
+        let mut interner = StaticInterner::<3>::new();
+        let a = interner.try_intern("#static a").unwrap();
+        let b = interner.try_intern("#static b").unwrap();
+        assert!(Atom::ptr_eq(interner.try_intern("#static a").unwrap(), a));
+        let c = interner.try_intern("#static c").unwrap();
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.try_intern("#static d"), None);
+        assert_eq!(interner.try_intern("#static b"), Some(b));
+        assert_eq!(interner.get("#static c"), Some(c));
+        assert_eq!(interner.get("#static d"), None);
+        assert_eq!(interner.len(), interner.capacity());
+    }
+}
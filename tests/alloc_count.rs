@@ -0,0 +1,23 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+// The allocation count is global, so this runs in its own test binary
+// where no other test can allocate atoms concurrently.
+#![cfg(feature = "count-allocs")]
+
+use atom_str::Atom;
+
+#[test]
+fn alloc_count_test() {
+    let before = Atom::alloc_count();
+    let atom = Atom::new("#alloc count");
+    assert_eq!(Atom::alloc_count(), before + 1);
+    assert!(Atom::ptr_eq(atom, Atom::new("#alloc count")));
+    let _ = Atom::new("");
+    assert_eq!(Atom::alloc_count(), before + 1);
+    Atom::reset_stats();
+    assert_eq!(Atom::alloc_count(), 0);
+    assert!(Atom::ptr_eq(atom, Atom::new("#alloc count")));
+    assert_eq!(Atom::alloc_count(), 0);
+}
@@ -0,0 +1,33 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+// The byte budget is global, so this runs in its own test binary where no
+// other test can be refused by the budget or change it.
+
+use atom_str::Atom;
+
+#[test]
+fn try_intern_budgeted_test() {
+    let existing = Atom::try_intern_budgeted("#budgeted existing").unwrap();
+    // Builds the ASCII table, which adds to the total.
+    let b = Atom::new("b");
+    let total = Atom::total_interned_bytes();
+    Atom::set_byte_budget(total);
+    let result = Atom::try_intern_budgeted("#budgeted new");
+    let repeat = Atom::try_intern_budgeted("#budgeted existing");
+    let ascii = Atom::try_intern_budgeted("b");
+    assert_eq!(Atom::total_interned_bytes(), total);
+    Atom::set_byte_budget(total + "#budgeted new".len());
+    let fits = Atom::try_intern_budgeted("#budgeted new");
+    assert_eq!(Atom::total_interned_bytes(), total + "#budgeted new".len());
+    assert!(Atom::try_intern_budgeted("#budgeted over").is_err());
+    Atom::set_byte_budget(usize::MAX);
+    let err = result.unwrap_err();
+    assert_eq!(err.requested_len(), "#budgeted new".len());
+    assert_eq!(err.budget(), total);
+    assert_eq!(fits.unwrap(), "#budgeted new");
+    assert_eq!(repeat, Ok(existing));
+    assert_eq!(ascii, Ok(b));
+    assert_eq!(Atom::try_intern_budgeted("#budgeted over").unwrap(), "#budgeted over");
+}
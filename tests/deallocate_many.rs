@@ -0,0 +1,31 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+// Deallocating atoms frees them from the global intern set, so this runs in
+// its own test binary where no other test can hold or snapshot atoms.
+
+use atom_str::Atom;
+
+#[test]
+fn deallocate_many_test() {
+    let strings = ["#deallocate a", "#deallocate b", "#deallocate c"];
+    let atoms: Vec<Atom> = strings.iter().map(|string| Atom::new(string)).collect();
+    let leaked = Atom::from_string(String::from("#deallocate leaked"));
+    let borrowed = Atom::new_static("#deallocate borrowed");
+    let kept = Atom::new("#deallocate kept");
+    let freed = [atoms[0], atoms[1], atoms[2], atoms[0], leaked, borrowed, Atom::new("a"), Atom::new("")];
+    unsafe {
+        Atom::deallocate_many(&freed);
+    }
+    for string in strings {
+        assert_eq!(Atom::get(string), None);
+    }
+    assert_eq!(Atom::get("#deallocate leaked"), None);
+    assert_eq!(Atom::get("#deallocate borrowed"), None);
+    assert_eq!(Atom::get("#deallocate kept"), Some(kept));
+    assert_eq!(Atom::new("a"), "a");
+    let fresh = Atom::new("#deallocate a");
+    assert_eq!(fresh, "#deallocate a");
+    assert_eq!(Atom::get("#deallocate a"), Some(fresh));
+}
@@ -0,0 +1,33 @@
+// Copyright (c) 2025-present Derek F.
+// Licensed under the MIT license.
+// See LICENSE file in project root for full license information.
+
+// Resetting the intern set frees every atom in it, so this runs in its own
+// test binary where no other test can hold atoms.
+#![cfg(feature = "reset")]
+
+use atom_str::Atom;
+
+#[test]
+fn reset_intern_set_test() {
+    let ascii = Atom::new("a");
+    let unique = Atom::new_unique("#reset unique");
+    let strings: Vec<String> = (0..100).map(|i| format!("#reset {i}")).collect();
+    for string in &strings {
+        let _ = Atom::new(string);
+    }
+    let before = Atom::intern_count();
+    let before_bytes = Atom::total_interned_bytes();
+    assert!(before >= 100);
+    unsafe { Atom::reset_intern_set() };
+    // Only the single-byte ASCII atoms remain.
+    let after = Atom::intern_count();
+    assert!(after <= 128);
+    assert!(Atom::total_interned_bytes() <= 128);
+    assert!(Atom::total_interned_bytes() < before_bytes);
+    assert!(Atom::ptr_eq(Atom::new("a"), ascii));
+    assert_eq!(unique, "#reset unique");
+    let again = Atom::new("#reset 0");
+    assert_eq!(again, "#reset 0");
+    assert_eq!(Atom::intern_count(), after + 1);
+}